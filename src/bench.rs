@@ -0,0 +1,162 @@
+use std::fmt;
+use std::hint::black_box;
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+
+// execucoes descartadas antes de medir, para aquecer caches, alocador e as threads do pool
+pub const DEFAULT_WARMUP: usize = 2;
+// execucoes medidas de cada operacao
+pub const DEFAULT_REPETITIONS: usize = 10;
+
+// quantas vezes cada operacao roda antes e durante a medicao
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BenchConfig {
+    pub warmup: usize,
+    pub repetitions: usize,
+}
+
+impl Default for BenchConfig {
+    fn default() -> BenchConfig {
+        BenchConfig { warmup: DEFAULT_WARMUP, repetitions: DEFAULT_REPETITIONS }
+    }
+}
+
+// estatisticas das repeticoes de uma medicao, todos os tempos em nanossegundos
+// nos relatorios JSON os tempos levam o sufixo _ns, como nas colunas do CSV
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Stats {
+    pub samples: usize,
+    #[serde(rename = "min_ns")]
+    pub min: f64,
+    #[serde(rename = "median_ns")]
+    pub median: f64,
+    #[serde(rename = "mean_ns")]
+    pub mean: f64,
+    // desvio padrao amostral (dividido por n - 1), zero com uma unica amostra
+    #[serde(rename = "stddev_ns")]
+    pub stddev: f64,
+}
+
+impl Stats {
+    // calcula as estatisticas dos tempos medidos, em nanossegundos
+    pub fn from_samples(samples: &[u64]) -> Stats {
+        assert!(!samples.is_empty(), "at least one sample is needed");
+        let mut sorted = samples.to_vec();
+        sorted.sort_unstable();
+        let n = sorted.len();
+
+        let median = if n % 2 == 1 {
+            sorted[n / 2] as f64
+        } else {
+            (sorted[n / 2 - 1] as f64 + sorted[n / 2] as f64) / 2.0
+        };
+        let mean = sorted.iter().map(|&t| t as f64).sum::<f64>() / n as f64;
+        let stddev = if n > 1 {
+            let squares: f64 = sorted.iter().map(|&t| (t as f64 - mean).powi(2)).sum();
+            (squares / (n - 1) as f64).sqrt()
+        } else {
+            0.0
+        };
+        Stats { samples: n, min: sorted[0] as f64, median, mean, stddev }
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "median {:.0} ns | min {:.0} ns | mean {:.0} ns | stddev {:.0} ns",
+            self.median, self.min, self.mean, self.stddev
+        )
+    }
+}
+
+// quantas vezes candidate e mais rapido que baseline, comparando as medianas
+// a mediana e usada em vez da media para que uma repeticao interrompida pelo sistema nao distorca o resultado
+pub fn speedup(baseline: &Stats, candidate: &Stats) -> f64 {
+    baseline.median / candidate.median.max(1.0)
+}
+
+// roda f config.warmup vezes sem medir, depois config.repetitions vezes medindo cada execucao
+// retorna as estatisticas e o resultado da ultima execucao, para ser conferido por quem chamou
+pub fn measure<R>(config: BenchConfig, mut f: impl FnMut() -> R) -> (Stats, R) {
+    for _ in 0..config.warmup {
+        black_box(f());
+    }
+    let repetitions = config.repetitions.max(1);
+    let mut samples = Vec::with_capacity(repetitions);
+    let mut result = None;
+    for _ in 0..repetitions {
+        // o resultado da repeticao anterior e liberado antes de medir, para nao entrar no tempo
+        drop(result.take());
+        let start = Instant::now();
+        let value = black_box(f());
+        samples.push(start.elapsed().as_nanos() as u64);
+        result = Some(value);
+    }
+    (Stats::from_samples(&samples), result.unwrap())
+}
+
+// linha do relatorio de uma medicao, com o speedup sobre a versao sequencial quando ela e passada
+pub fn report(label: &str, stats: &Stats, sequential: Option<&Stats>) -> String {
+    match sequential {
+        Some(baseline) => format!("{}: {} | speedup {:.2}x", label, stats, speedup(baseline, stats)),
+        None => format!("{}: {}", label, stats),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() <= 1e-9 * b.abs().max(1.0)
+    }
+
+    // com quantidade impar a mediana e o elemento do meio, a ordem das amostras nao importa
+    #[test]
+    fn stats_with_odd_count() {
+        let stats = Stats::from_samples(&[30, 10, 50, 20, 40]);
+        assert_eq!(stats.samples, 5);
+        assert_eq!((stats.min, stats.median, stats.mean), (10.0, 30.0, 30.0));
+        // soma dos quadrados 1000, dividida por n - 1 = 4
+        assert!(close(stats.stddev, 250f64.sqrt()));
+    }
+
+    // com quantidade par a mediana e a media dos dois do meio
+    #[test]
+    fn stats_with_even_count() {
+        let stats = Stats::from_samples(&[4, 1, 3, 100]);
+        assert_eq!((stats.min, stats.median, stats.mean), (1.0, 3.5, 27.0));
+        // desvios -23, -26, -24 e 73 da media: soma dos quadrados 7110, dividida por n - 1 = 3
+        assert!(close(stats.stddev, 2370f64.sqrt()));
+
+        let single = Stats::from_samples(&[7]);
+        assert_eq!((single.median, single.mean, single.stddev), (7.0, 7.0, 0.0));
+    }
+
+    // o speedup compara as medianas, e uma mediana zero conta como 1 ns
+    #[test]
+    fn speedup_uses_medians() {
+        let baseline = Stats::from_samples(&[300, 300, 900]);
+        let candidate = Stats::from_samples(&[100, 100, 100]);
+        assert_eq!(speedup(&baseline, &candidate), 3.0);
+        assert_eq!(speedup(&candidate, &baseline), 1.0 / 3.0);
+        assert_eq!(speedup(&baseline, &Stats::from_samples(&[0])), 300.0);
+        assert_eq!(report("Sum", &candidate, Some(&baseline)), "Sum: median 100 ns | min 100 ns | mean 100 ns | stddev 0 ns | speedup 3.00x");
+    }
+
+    // as execucoes de aquecimento nao entram nas amostras, e sempre ha pelo menos uma medida
+    #[test]
+    fn measure_counts_runs() {
+        let mut calls = 0;
+        let (stats, last) = measure(BenchConfig { warmup: 2, repetitions: 3 }, || {
+            calls += 1;
+            calls
+        });
+        assert_eq!((stats.samples, last, calls), (3, 5, 5));
+        let (stats, _) = measure(BenchConfig { warmup: 0, repetitions: 0 }, || ());
+        assert_eq!(stats.samples, 1);
+    }
+}
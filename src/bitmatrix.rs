@@ -0,0 +1,390 @@
+use crate::matrix::{Matrix, MatrixError};
+use crate::ops::split_rows_mut;
+use crate::pool;
+
+// quantidade de bits de cada palavra
+const WORD: usize = 64;
+// quantidade de linhas de b juntadas em cada tabela do metodo dos quatro russos, 2^8 = 256 combinacoes
+const GROUP: usize = 8;
+
+// matriz booleana compacta: cada linha e guardada em palavras u64, 64 celulas por palavra
+// a celula (i, j) e o bit j % 64 da palavra j / 64 da linha i
+// os bits que sobram na ultima palavra de cada linha ficam sempre em zero,
+// assim a comparacao e a contagem de bits nao precisam ignora-los
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitMatrix {
+    rows: usize,
+    cols: usize,
+    words: usize,
+    data: Vec<u64>,
+}
+
+// operacoes elemento a elemento entre duas BitMatrix
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BitOp {
+    And,
+    Or,
+    Xor,
+}
+
+impl BitOp {
+    fn apply(self, a: u64, b: u64) -> u64 {
+        match self {
+            BitOp::And => a & b,
+            BitOp::Or => a | b,
+            BitOp::Xor => a ^ b,
+        }
+    }
+}
+
+impl BitMatrix {
+    // cria uma matriz rows x cols com todas as celulas em false
+    pub fn new(rows: usize, cols: usize) -> BitMatrix {
+        let words = cols.div_ceil(WORD);
+        BitMatrix { rows, cols, words, data: vec![0; rows * words] }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn shape(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    // palavras u64 da linha i
+    pub fn row_words(&self, i: usize) -> &[u64] {
+        &self.data[i * self.words..(i + 1) * self.words]
+    }
+
+    pub fn get(&self, i: usize, j: usize) -> bool {
+        assert!(i < self.rows && j < self.cols, "index ({}, {}) out of bounds for {}x{}", i, j, self.rows, self.cols);
+        self.data[i * self.words + j / WORD] >> (j % WORD) & 1 == 1
+    }
+
+    pub fn set(&mut self, i: usize, j: usize, value: bool) {
+        assert!(i < self.rows && j < self.cols, "index ({}, {}) out of bounds for {}x{}", i, j, self.rows, self.cols);
+        let word = &mut self.data[i * self.words + j / WORD];
+        if value {
+            *word |= 1 << (j % WORD);
+        } else {
+            *word &= !(1 << (j % WORD));
+        }
+    }
+
+    // converte de uma matriz de bool, que usa 1 byte por celula
+    pub fn from_bools(m: &Matrix<bool>) -> BitMatrix {
+        let mut bits = BitMatrix::new(m.rows(), m.cols());
+        for i in 0..m.rows() {
+            for (j, &cell) in m.row(i).iter().enumerate() {
+                if cell {
+                    bits.set(i, j, true);
+                }
+            }
+        }
+        bits
+    }
+
+    // converte de volta para uma matriz de bool
+    pub fn to_bools(&self) -> Matrix<bool> {
+        let mut m = Matrix::filled(self.rows, self.cols, false);
+        for i in 0..self.rows {
+            for j in 0..self.cols {
+                m[(i, j)] = self.get(i, j);
+            }
+        }
+        m
+    }
+
+    // quantidade de celulas em true
+    pub fn count_ones(&self) -> usize {
+        self.data.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    // mascara com os bits validos da ultima palavra de cada linha
+    fn last_word_mask(&self) -> u64 {
+        match self.cols % WORD {
+            0 => u64::MAX,
+            used => (1 << used) - 1,
+        }
+    }
+
+    // inverte todas as celulas, mantendo em zero os bits que sobram no fim de cada linha
+    pub fn not(&self) -> BitMatrix {
+        let mut result = self.clone();
+        not_rows(&mut result.data, self.words, self.last_word_mask());
+        result
+    }
+
+    // transposta: a celula (i, j) vai para (j, i)
+    pub fn transpose(&self) -> BitMatrix {
+        let mut result = BitMatrix::new(self.cols, self.rows);
+        if !result.data.is_empty() {
+            transpose_rows(self, 0, &mut result.data);
+        }
+        result
+    }
+}
+
+// inverte as palavras de um grupo de linhas inteiras, com words palavras por linha
+// mask zera os bits que sobram na ultima palavra de cada linha
+// sem colunas nao ha palavras para inverter
+fn not_rows(out: &mut [u64], words: usize, mask: u64) {
+    if words == 0 {
+        return;
+    }
+    for row in out.chunks_mut(words) {
+        for word in row.iter_mut() {
+            *word = !*word;
+        }
+        row[words - 1] &= mask;
+    }
+}
+
+// mascara com os bits de lo ate hi - 1 ligados, com 0 <= lo <= hi <= 64
+fn bit_range(lo: usize, hi: usize) -> u64 {
+    let below_hi = if hi == WORD { u64::MAX } else { (1 << hi) - 1 };
+    below_hi & !((1u64 << lo) - 1)
+}
+
+// linhas start.. da transposta de m, que sao as colunas start.. de m
+// percorre so os bits em true dessas colunas em cada palavra, pulando as palavras zeradas de uma vez
+fn transpose_rows(m: &BitMatrix, start: usize, out: &mut [u64]) {
+    let out_words = m.rows.div_ceil(WORD);
+    let end = start + out.len() / out_words;
+    for i in 0..m.rows {
+        let row = m.row_words(i);
+        let first = start / WORD;
+        for (w, &word) in row.iter().enumerate().take(end.div_ceil(WORD)).skip(first) {
+            let lo = start.saturating_sub(w * WORD);
+            let hi = (end - w * WORD).min(WORD);
+            let mut rest = word & bit_range(lo, hi);
+            while rest != 0 {
+                let j = w * WORD + rest.trailing_zeros() as usize;
+                out[(j - start) * out_words + i / WORD] |= 1 << (i % WORD);
+                rest &= rest - 1;
+            }
+        }
+    }
+}
+
+// verifica se as duas matrizes tem o mesmo tamanho, usado pelas operacoes elemento a elemento
+fn check_same_shape(a: &BitMatrix, b: &BitMatrix, op: &'static str) -> Result<(), MatrixError> {
+    if a.rows == 0 || a.cols == 0 || b.rows == 0 || b.cols == 0 {
+        return Err(MatrixError::Empty);
+    }
+    if a.shape() != b.shape() {
+        return Err(MatrixError::ShapeMismatch { left: a.shape(), right: b.shape(), op });
+    }
+    Ok(())
+}
+
+// verifica se as colunas de a sao iguais as linhas de b, usado pela multiplicacao
+fn check_mul_shape(a: &BitMatrix, b: &BitMatrix) -> Result<(), MatrixError> {
+    if a.rows == 0 || a.cols == 0 || b.rows == 0 || b.cols == 0 {
+        return Err(MatrixError::Empty);
+    }
+    if a.cols != b.rows {
+        return Err(MatrixError::ShapeMismatch { left: a.shape(), right: b.shape(), op: "mul_bitmatrix" });
+    }
+    Ok(())
+}
+
+// AND, OR ou XOR elemento a elemento, 64 celulas por operacao
+pub fn bitwise_sequential(a: &BitMatrix, b: &BitMatrix, op: BitOp) -> Result<BitMatrix, MatrixError> {
+    check_same_shape(a, b, "bitwise")?;
+    let mut result = a.clone();
+    for (x, &y) in result.data.iter_mut().zip(&b.data) {
+        *x = op.apply(*x, y);
+    }
+    Ok(result)
+}
+
+// AND, OR ou XOR elemento a elemento usando threads, cada parte fica com um grupo de linhas
+pub fn bitwise_threads(a: &BitMatrix, b: &BitMatrix, op: BitOp, thr: usize) -> Result<BitMatrix, MatrixError> {
+    check_same_shape(a, b, "bitwise")?;
+    let mut result = a.clone();
+    let words = a.words;
+
+    pool::global().scope(|s| {
+        for (start, chunk) in split_rows_mut(&mut result.data, a.rows, words, thr) {
+            let other = &b.data[start * words..start * words + chunk.len()];
+            s.spawn(move || {
+                for (x, &y) in chunk.iter_mut().zip(other) {
+                    *x = op.apply(*x, y);
+                }
+            });
+        }
+    });
+    Ok(result)
+}
+
+// NOT usando threads, cada parte inverte um grupo de linhas
+pub fn not_threads(a: &BitMatrix, thr: usize) -> BitMatrix {
+    let mut result = a.clone();
+    let (words, mask) = (a.words, a.last_word_mask());
+    if words > 0 {
+        pool::global().scope(|s| {
+            for (_, chunk) in split_rows_mut(&mut result.data, a.rows, words, thr) {
+                s.spawn(move || not_rows(chunk, words, mask));
+            }
+        });
+    }
+    result
+}
+
+// transposta usando threads: cada parte fica com um grupo de linhas da transposta (colunas de a)
+// e le as palavras de a que cobrem essas colunas
+pub fn transpose_threads(a: &BitMatrix, thr: usize) -> BitMatrix {
+    let mut result = BitMatrix::new(a.cols, a.rows);
+    let words = result.words;
+    if words > 0 {
+        pool::global().scope(|s| {
+            for (start, chunk) in split_rows_mut(&mut result.data, a.cols, words, thr) {
+                s.spawn(move || transpose_rows(a, start, chunk));
+            }
+        });
+    }
+    result
+}
+
+// metodo dos quatro russos: para cada grupo de 8 linhas de b, guarda o OR de todas as 256 combinacoes
+// dessas linhas; assim cada byte de uma linha de a vira uma unica consulta na tabela,
+// em vez de 8 ORs separados
+// table[idx * words..(idx + 1) * words] fica com o OR das linhas group * 8 + p de b com o bit p de idx ligado
+// no ultimo grupo, com menos de 8 linhas, as entradas de cima nao sao usadas, pois os bits de a depois
+// da ultima coluna sao sempre zero
+fn build_table(b: &BitMatrix, group: usize, table: &mut [u64]) {
+    let words = b.words;
+    let base = group * GROUP;
+    let len = GROUP.min(b.rows - base);
+    for idx in 1..(1usize << len) {
+        // idx e a combinacao de idx sem o bit mais baixo, mais a linha desse bit
+        let bit = idx.trailing_zeros() as usize;
+        let prev = idx & (idx - 1);
+        let row = b.row_words(base + bit);
+        for w in 0..words {
+            table[idx * words + w] = table[prev * words + w] | row[w];
+        }
+    }
+}
+
+// calcula as linhas start.. do produto, um grupo de linhas de b por vez:
+// monta a tabela do grupo e usa nas linhas de out antes de montar a do proximo grupo,
+// assim so existe uma tabela de 256 * words palavras por vez, em vez de uma por grupo
+fn mul_bit_rows(a: &BitMatrix, b: &BitMatrix, start: usize, out: &mut [u64]) {
+    let words = b.words;
+    let mut table = vec![0u64; (1 << GROUP) * words];
+    for g in 0..b.rows.div_ceil(GROUP) {
+        build_table(b, g, &mut table);
+        for (r, out_row) in out.chunks_mut(words).enumerate() {
+            // como 64 e multiplo de 8, o grupo nunca fica dividido entre duas palavras
+            let a_row = a.row_words(start + r);
+            let idx = (a_row[g * GROUP / WORD] >> (g * GROUP % WORD)) as usize & ((1 << GROUP) - 1);
+            if idx != 0 {
+                let combined = &table[idx * words..(idx + 1) * words];
+                for (x, &y) in out_row.iter_mut().zip(combined) {
+                    *x |= y;
+                }
+            }
+        }
+    }
+}
+
+// produto booleano (OR, AND) usando o metodo dos quatro russos
+// o resultado e o mesmo de mul_matrix_semiring_sequential::<Boolean> nas matrizes de bool
+pub fn mul_bitmatrix_sequential(a: &BitMatrix, b: &BitMatrix) -> Result<BitMatrix, MatrixError> {
+    check_mul_shape(a, b)?;
+    let mut result = BitMatrix::new(a.rows, b.cols);
+    mul_bit_rows(a, b, 0, &mut result.data);
+    Ok(result)
+}
+
+// produto booleano usando threads: as linhas da saida sao divididas entre as threads, igual a mul_matrix_threads,
+// e cada thread monta as suas tabelas, uma por vez; montar a tabela custa 256 * words palavras por grupo,
+// pouco perto das linhas de cada thread, e assim as threads nao precisam esperar umas pelas outras a cada grupo
+pub fn mul_bitmatrix_threads(a: &BitMatrix, b: &BitMatrix, thr: usize) -> Result<BitMatrix, MatrixError> {
+    check_mul_shape(a, b)?;
+    let mut result = BitMatrix::new(a.rows, b.cols);
+    let words = result.words;
+    pool::global().scope(|s| {
+        for (start, chunk) in split_rows_mut(&mut result.data, a.rows, words, thr) {
+            s.spawn(move || mul_bit_rows(a, b, start, chunk));
+        }
+    });
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::create_bool_matrix_from_random;
+    use crate::semiring::{mul_matrix_semiring_sequential, Boolean};
+    use crate::testing::assert_threads_match;
+
+    // os tamanhos nao sao multiplos de 64 nem de 8, para testar as sobras das palavras e dos grupos
+    #[test]
+    fn conversions_not_and_transpose() {
+        let a = create_bool_matrix_from_random(70, 130);
+        let bits = BitMatrix::from_bools(&a);
+        assert_eq!(bits.to_bools(), a);
+
+        let ones = a.as_slice().iter().filter(|&&x| x).count();
+        assert_eq!(bits.count_ones(), ones);
+        assert_eq!(bits.not().count_ones(), bits.rows() * bits.cols() - ones);
+        assert_eq!(bits.not().not(), bits);
+
+        assert_threads_match(bits.not(), |thr| not_threads(&bits, thr));
+
+        let transposed = bits.transpose();
+        assert_eq!(transposed.shape(), (130, 70));
+        assert!((0..70).all(|i| (0..130).all(|j| transposed.get(j, i) == a[(i, j)])));
+        assert_eq!(transposed.transpose(), bits);
+        assert_threads_match(transposed.clone(), |thr| transpose_threads(&bits, thr));
+    }
+
+    // matrizes sem linhas ou sem colunas sao validas: NOT devolve a mesma forma e a transposta troca as duas
+    #[test]
+    fn not_and_transpose_without_rows_or_columns() {
+        for (rows, cols) in [(4, 0), (0, 5), (0, 0)] {
+            let m = BitMatrix::new(rows, cols);
+            assert_eq!(m.not(), m);
+            assert_threads_match(m.clone(), |thr| not_threads(&m, thr));
+            let transposed = BitMatrix::new(cols, rows);
+            assert_eq!(m.transpose(), transposed);
+            assert_threads_match(transposed, |thr| transpose_threads(&m, thr));
+        }
+    }
+
+    // as operacoes elemento a elemento comparadas com as mesmas operacoes em Matrix<bool>
+    #[test]
+    fn bitwise_matches_bools() -> Result<(), MatrixError> {
+        let a = create_bool_matrix_from_random(70, 130);
+        let c = create_bool_matrix_from_random(70, 130);
+        let (bits_a, bits_c) = (BitMatrix::from_bools(&a), BitMatrix::from_bools(&c));
+        for (op, f) in [(BitOp::And, (|x, y| x && y) as fn(bool, bool) -> bool), (BitOp::Or, |x, y| x || y), (BitOp::Xor, |x, y| x != y)] {
+            let expected = Matrix::from_vec(70, 130, a.as_slice().iter().zip(c.as_slice()).map(|(&x, &y)| f(x, y)).collect())?;
+            assert_eq!(bitwise_sequential(&bits_a, &bits_c, op)?.to_bools(), expected);
+            assert_threads_match(bitwise_sequential(&bits_a, &bits_c, op), |thr| bitwise_threads(&bits_a, &bits_c, op, thr));
+        }
+        assert!(matches!(bitwise_sequential(&bits_a, &bits_a.transpose(), BitOp::And), Err(MatrixError::ShapeMismatch { .. })));
+        Ok(())
+    }
+
+    // o produto pelos quatro russos comparado com o semianel booleano
+    #[test]
+    fn mul_matches_boolean_semiring() -> Result<(), MatrixError> {
+        let a = create_bool_matrix_from_random(70, 130);
+        let b = create_bool_matrix_from_random(130, 67);
+        let (bits_a, bits_b) = (BitMatrix::from_bools(&a), BitMatrix::from_bools(&b));
+        let product = mul_bitmatrix_sequential(&bits_a, &bits_b)?;
+        assert_eq!(product.to_bools(), mul_matrix_semiring_sequential::<Boolean>(&a, &b)?);
+        assert_threads_match(Ok(product), |thr| mul_bitmatrix_threads(&bits_a, &bits_b, thr));
+        assert!(matches!(mul_bitmatrix_sequential(&bits_a, &bits_a), Err(MatrixError::ShapeMismatch { .. })));
+        Ok(())
+    }
+}
@@ -0,0 +1,230 @@
+use crate::lu::PIVOT_TOLERANCE;
+use crate::matrix::{Matrix, MatrixError};
+use crate::ops::{check_square, split_rows_mut};
+use crate::pool;
+
+// quantidade padrao de colunas de cada bloco da decomposicao
+pub const DEFAULT_BLOCK: usize = 32;
+
+// decomposicao de Cholesky de uma matriz simetrica definida positiva: A = L * L^T
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cholesky {
+    // triangular inferior, com a diagonal positiva
+    l: Matrix<f64>,
+}
+
+impl Cholesky {
+    // fator triangular inferior L
+    pub fn l(&self) -> &Matrix<f64> {
+        &self.l
+    }
+
+    // resolve A * x = b com L * y = b e depois L^T * x = y
+    pub fn solve(&self, b: &[f64]) -> Result<Vec<f64>, MatrixError> {
+        let n = self.l.rows();
+        if b.len() != n {
+            return Err(MatrixError::Length { expected: n, found: b.len() });
+        }
+        let mut x = b.to_vec();
+        for i in 0..n {
+            let row = self.l.row(i);
+            let sum: f64 = (0..i).map(|k| row[k] * x[k]).sum();
+            x[i] = (x[i] - sum) / row[i];
+        }
+        for i in (0..n).rev() {
+            let sum: f64 = (i + 1..n).map(|k| self.l[(k, i)] * x[k]).sum();
+            x[i] = (x[i] - sum) / self.l[(i, i)];
+        }
+        Ok(x)
+    }
+
+    // o determinante de A e o quadrado do produto da diagonal de L
+    pub fn determinant(&self) -> f64 {
+        (0..self.l.rows()).map(|i| self.l[(i, i)]).product::<f64>().powi(2)
+    }
+}
+
+// verifica se a matriz e simetrica, com a mesma tolerancia relativa dos pivos
+// scale e o tamanho dos elementos da matriz, as diferencas sao comparadas com ele
+pub(crate) fn check_symmetric(a: &Matrix<f64>, scale: f64) -> Result<(), MatrixError> {
+    for i in 0..a.rows() {
+        for j in 0..i {
+            if (a[(i, j)] - a[(j, i)]).abs() > PIVOT_TOLERANCE * scale {
+                return Err(MatrixError::NotSymmetric { row: i, col: j });
+            }
+        }
+    }
+    Ok(())
+}
+
+// decomposicao sem blocos do bloco diagonal, colunas kb ate kend, ja com as atualizacoes dos blocos anteriores
+// um pivo zero ou negativo quer dizer que a matriz nao e definida positiva
+fn factor_diagonal(l: &mut Matrix<f64>, kb: usize, kend: usize, tol: f64) -> Result<(), MatrixError> {
+    for j in kb..kend {
+        let d = l[(j, j)] - (kb..j).map(|p| l[(j, p)] * l[(j, p)]).sum::<f64>();
+        if d.is_nan() || d <= tol {
+            return Err(MatrixError::NotPositiveDefinite { pivot: j });
+        }
+        let pivot = d.sqrt();
+        l[(j, j)] = pivot;
+        for i in j + 1..kend {
+            let sum: f64 = (kb..j).map(|p| l[(i, p)] * l[(j, p)]).sum();
+            l[(i, j)] = (l[(i, j)] - sum) / pivot;
+        }
+    }
+    Ok(())
+}
+
+// colunas kb ate kend das linhas abaixo do bloco diagonal (o painel), resolvendo o sistema triangular do bloco
+// out tem as linhas kend + start, kend + start + 1, ... do painel, com kend - kb colunas cada
+fn panel_rows(l: &Matrix<f64>, kb: usize, kend: usize, start: usize, out: &mut [f64]) {
+    let width = kend - kb;
+    for (r, row) in out.chunks_mut(width).enumerate() {
+        let i = kend + start + r;
+        for j in kb..kend {
+            let sum: f64 = (kb..j).map(|p| row[p - kb] * l[(j, p)]).sum();
+            row[j - kb] = (l[(i, j)] - sum) / l[(j, j)];
+        }
+    }
+}
+
+// atualiza as linhas first, first + 1, ... abaixo do bloco: copia o painel para as colunas kb ate kend
+// e subtrai painel * painel^T da parte triangular inferior a direita do bloco
+// cada linha so le o painel, que fica separado da matriz, entao as linhas podem ser divididas entre as threads
+fn update_rows(rows: &mut [f64], n: usize, first: usize, kb: usize, kend: usize, panel: &[f64]) {
+    let width = kend - kb;
+    for (r, row) in rows.chunks_mut(n).enumerate() {
+        let i = first + r;
+        let pi = &panel[(i - kend) * width..(i - kend + 1) * width];
+        row[kb..kend].copy_from_slice(pi);
+        for j in kend..=i {
+            let pj = &panel[(j - kend) * width..(j - kend + 1) * width];
+            row[j] -= pi.iter().zip(pj).map(|(x, y)| x * y).sum::<f64>();
+        }
+    }
+}
+
+// Cholesky em blocos de block colunas, da esquerda para a direita: fatora o bloco diagonal,
+// calcula o painel abaixo dele e subtrai a contribuicao do painel do resto da matriz
+// thr None faz o painel e a atualizacao sequencialmente, Some(thr) divide as linhas entre as threads,
+// igual a mul_matrix_threads, com as mesmas contas em cada elemento, entao o resultado e identico
+fn factor(a: &Matrix<f64>, block: usize, thr: Option<usize>) -> Result<Cholesky, MatrixError> {
+    check_square(a, "cholesky")?;
+    let n = a.rows();
+    let scale = (0..n).fold(0.0, |s: f64, i| s.max(a[(i, i)].abs()));
+    check_symmetric(a, scale)?;
+    let tol = PIVOT_TOLERANCE * scale;
+    let block = block.max(1);
+    let mut l = a.clone();
+
+    for kb in (0..n).step_by(block) {
+        let kend = (kb + block).min(n);
+        factor_diagonal(&mut l, kb, kend, tol)?;
+
+        let (rows, width) = (n - kend, kend - kb);
+        let mut panel = vec![0.0; rows * width];
+        let trailing_start = kend * n;
+        match thr {
+            None => {
+                panel_rows(&l, kb, kend, 0, &mut panel);
+                update_rows(&mut l.as_mut_slice()[trailing_start..], n, kend, kb, kend, &panel);
+            }
+            Some(thr) => {
+                let source = &l;
+                pool::global().scope(|s| {
+                    for (start, chunk) in split_rows_mut(&mut panel, rows, width, thr) {
+                        s.spawn(move || panel_rows(source, kb, kend, start, chunk));
+                    }
+                });
+                let panel = &panel;
+                pool::global().scope(|s| {
+                    for (start, chunk) in split_rows_mut(&mut l.as_mut_slice()[trailing_start..], rows, n, thr) {
+                        s.spawn(move || update_rows(chunk, n, kend + start, kb, kend, panel));
+                    }
+                });
+            }
+        }
+    }
+
+    // o que sobrou acima da diagonal ainda e a matriz original
+    for i in 0..n {
+        l.row_mut(i)[i + 1..].iter_mut().for_each(|x| *x = 0.0);
+    }
+    Ok(Cholesky { l })
+}
+
+// decomposicao de Cholesky sequencial, em blocos de block colunas
+// da erro NotSymmetric se a nao for simetrica e NotPositiveDefinite com o primeiro pivo que nao e positivo
+pub fn cholesky_sequential(a: &Matrix<f64>, block: usize) -> Result<Cholesky, MatrixError> {
+    factor(a, block, None)
+}
+
+// decomposicao de Cholesky em blocos usando threads no painel e na atualizacao do resto da matriz
+pub fn cholesky_threads(a: &Matrix<f64>, block: usize, thr: usize) -> Result<Cholesky, MatrixError> {
+    factor(a, block, Some(thr))
+}
+
+// resolve a * x = b com a simetrica definida positiva, cerca de duas vezes mais rapido que pela LU
+pub fn solve_spd(a: &Matrix<f64>, b: &[f64], thr: usize) -> Result<Vec<f64>, MatrixError> {
+    cholesky_threads(a, DEFAULT_BLOCK, thr)?.solve(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lu::lu_sequential;
+    use crate::numeric::approx_eq;
+    use crate::ops::mul_matrix_sequential;
+    use crate::random::{create_f64_matrix_from_random, create_spd_matrix_from_random};
+    use crate::testing::{assert_threads_match, LINALG_TOLERANCE};
+
+    // L triangular inferior com diagonal positiva e L * L^T = A, com qualquer tamanho de bloco
+    #[test]
+    fn factors() -> Result<(), MatrixError> {
+        let n = 50;
+        let a = create_spd_matrix_from_random(n, 10);
+        let chol = cholesky_sequential(&a, DEFAULT_BLOCK)?;
+        assert_threads_match(Ok(chol.clone()), |thr| cholesky_threads(&a, DEFAULT_BLOCK, thr));
+
+        let l = chol.l();
+        assert!((0..n).all(|i| l[(i, i)] > 0.0 && (i + 1..n).all(|j| l[(i, j)] == 0.0)));
+        assert!(approx_eq(&mul_matrix_sequential(l, &l.transpose())?, &a, LINALG_TOLERANCE));
+        // blocos de outros tamanhos, inclusive sem blocos e um bloco so, mudam so a ordem das contas
+        for block in [1, 7, 64] {
+            assert!(approx_eq(cholesky_threads(&a, block, 3)?.l(), l, LINALG_TOLERANCE));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn solve_and_determinant() -> Result<(), MatrixError> {
+        let n = 50;
+        let a = create_spd_matrix_from_random(n, 10);
+        let expected = create_f64_matrix_from_random(n, 1, -10, 10);
+        let b = mul_matrix_sequential(&a, &expected)?;
+        let x = Matrix::from_vec(n, 1, solve_spd(&a, b.as_slice(), 3)?)?;
+        assert!(approx_eq(&x, &expected, LINALG_TOLERANCE));
+        let det = lu_sequential(&a)?.determinant();
+        assert!((cholesky_sequential(&a, DEFAULT_BLOCK)?.determinant() / det - 1.0).abs() < LINALG_TOLERANCE);
+        Ok(())
+    }
+
+    // entradas que nao sao simetricas definidas positivas
+    #[test]
+    fn not_spd() -> Result<(), MatrixError> {
+        let a = create_spd_matrix_from_random(50, 10);
+        // diagonal negativa na linha 5: as colunas antes dela continuam validas, o pivo 5 e o primeiro a falhar
+        let mut negative = a.clone();
+        negative[(5, 5)] = -negative[(5, 5)];
+        assert_eq!(cholesky_sequential(&negative, 4), Err(MatrixError::NotPositiveDefinite { pivot: 5 }));
+        assert_threads_match(Err(MatrixError::NotPositiveDefinite { pivot: 5 }), |thr| cholesky_threads(&negative, 4, thr));
+        // simetrica com autovalores 3 e -1
+        let indefinite = Matrix::try_from(vec![vec![1.0, 2.0], vec![2.0, 1.0]])?;
+        assert_eq!(cholesky_sequential(&indefinite, DEFAULT_BLOCK), Err(MatrixError::NotPositiveDefinite { pivot: 1 }));
+        let mut asymmetric = a.clone();
+        asymmetric[(3, 7)] += 1.0;
+        assert_eq!(cholesky_sequential(&asymmetric, DEFAULT_BLOCK), Err(MatrixError::NotSymmetric { row: 7, col: 3 }));
+        assert!(matches!(cholesky_sequential(&Matrix::new(2, 3), DEFAULT_BLOCK), Err(MatrixError::NotSquare { .. })));
+        Ok(())
+    }
+}
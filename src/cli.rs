@@ -0,0 +1,376 @@
+use std::error::Error;
+use std::fmt;
+use std::path::PathBuf;
+
+use aoc3::bench::BenchConfig;
+use aoc3::pool;
+use aoc3::report::ReportFormat;
+
+// texto mostrado com --help
+pub const USAGE: &str = "\
+Matrix operations with threads
+
+Usage: AOC3 [COMMAND] [OPTIONS]
+
+Commands:
+  all     run the sum, sub and mul benchmarks and the extra benchmarks (default)
+  sum     benchmark the sum
+  sub     benchmark the subtraction
+  mul     benchmark the multiplication, with the partitioned, blocked and Strassen variants
+  sweep   time sum, sub, mul and a Life update for every size in --sizes and every
+          thread count in --threads, with speedup, efficiency and serial fraction
+
+Options:
+  -r, --rows N        rows of the random matrices (default 100)
+  -c, --cols N        columns of the random matrices (default 100)
+  -n, --size N        same as --rows N --cols N
+      --min N         smallest random element (default 0)
+      --max N         random elements are smaller than N (default 10)
+  -t, --threads LIST  comma separated thread counts for the threaded versions,
+                      0 or auto uses one thread per core (default 10, and for sweep
+                      the powers of two up to the number of cores)
+      --sizes LIST    comma separated square matrix sizes for sweep (default 100,200,400)
+      --pool-size N   threads in the shared pool, 0 or auto uses one thread per core
+                      (default: the number of cores or the largest count in --threads,
+                      whichever is larger)
+      --warmup N      runs before measuring each operation (default 2)
+      --reps N        measured runs of each operation (default 10)
+  -s, --seed N        seed for the random matrices and the Life board (default: a new one,
+                      printed at the start so the run can be repeated)
+  -a, --left FILE     read the left matrix from FILE instead of generating it
+  -b, --right FILE    read the right matrix from FILE instead of generating it
+  -o, --output FILE   write the result to FILE, only with sum, sub or mul
+      --report FILE   write the timings, seed and host information to FILE, as .csv or .json
+      --compare FILE  compare the timings with a report written before by --report
+      --max-regression PCT
+                      with --compare, exit with an error when any case is more than
+                      PCT percent slower than in the baseline (default 10)
+      --life          open the Game of Life windows after the benchmarks
+  -h, --help          print this help
+
+Files can be .csv, .tsv, .mtx (Matrix Market) or .bin, chosen by the extension.
+";
+
+// codigos de saida do programa: erro nos argumentos e erro ao rodar
+pub const EXIT_USAGE: i32 = 2;
+pub const EXIT_FAILURE: i32 = 1;
+
+// qual parte do programa rodar
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    All,
+    Sum,
+    Sub,
+    Mul,
+    Sweep,
+    Help,
+}
+
+// opcoes lidas da linha de comando, ja com os valores padrao preenchidos
+#[derive(Debug, Clone, PartialEq)]
+pub struct Options {
+    pub command: Command,
+    pub rows: usize,
+    pub cols: usize,
+    pub min: i32,
+    pub max: i32,
+    pub threads: Vec<usize>,
+    pub sizes: Vec<usize>,
+    // tamanho do pool pedido com --pool-size, None escolhe a partir de threads
+    pub pool_size: Option<usize>,
+    pub seed: Option<u64>,
+    pub bench: BenchConfig,
+    pub left: Option<PathBuf>,
+    pub right: Option<PathBuf>,
+    pub output: Option<PathBuf>,
+    pub report: Option<PathBuf>,
+    pub compare: Option<PathBuf>,
+    pub max_regression: f64,
+    pub life: bool,
+}
+
+impl Default for Options {
+    fn default() -> Options {
+        Options {
+            command: Command::All,
+            rows: 100,
+            cols: 100,
+            min: 0,
+            max: 10,
+            threads: vec![10],
+            sizes: vec![100, 200, 400],
+            pool_size: None,
+            seed: None,
+            bench: BenchConfig::default(),
+            left: None,
+            right: None,
+            output: None,
+            report: None,
+            compare: None,
+            max_regression: 10.0,
+            life: false,
+        }
+    }
+}
+
+impl Options {
+    // quantidade de threads do pool global: o valor de --pool-size ou, sem ele,
+    // o maior entre a quantidade de nucleos e as quantidades pedidas em --threads,
+    // para que cada quantidade medida tenha de fato essas threads rodando
+    pub fn pool_threads(&self) -> usize {
+        match self.pool_size {
+            Some(size) => pool::resolve_threads(size),
+            None => self.threads.iter().map(|&t| pool::resolve_threads(t)).fold(pool::default_size(), usize::max),
+        }
+    }
+}
+
+// erros nos argumentos, o programa mostra a mensagem e sai com codigo diferente de zero
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CliError {
+    // argumento que nao e nenhum comando ou opcao conhecida
+    Unknown(String),
+    // a opcao precisa de um valor, mas ele nao foi passado
+    MissingValue(String),
+    // o valor passado para a opcao nao e valido
+    InvalidValue { option: String, value: String },
+    // as opcoes fazem sentido sozinhas, mas nao juntas
+    Conflict(&'static str),
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CliError::Unknown(arg) => write!(f, "unknown argument {:?}", arg),
+            CliError::MissingValue(option) => write!(f, "missing value for {}", option),
+            CliError::InvalidValue { option, value } => write!(f, "invalid value {:?} for {}", value, option),
+            CliError::Conflict(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl Error for CliError {}
+
+fn parse_number<T: std::str::FromStr>(option: &str, value: &str) -> Result<T, CliError> {
+    value.parse().map_err(|_| CliError::InvalidValue { option: option.to_string(), value: value.to_string() })
+}
+
+// como parse_number, mas nao aceita zero, usado nas dimensoes e nas repeticoes
+fn parse_positive(option: &str, value: &str) -> Result<usize, CliError> {
+    match parse_number(option, value)? {
+        0 => Err(CliError::InvalidValue { option: option.to_string(), value: value.to_string() }),
+        n => Ok(n),
+    }
+}
+
+// lista de quantidades de threads separadas por virgula, "auto" vira pool::AUTO
+fn parse_threads(option: &str, value: &str) -> Result<Vec<usize>, CliError> {
+    value
+        .split(',')
+        .map(|part| match part.trim() {
+            "auto" => Ok(pool::AUTO),
+            part => parse_number(option, part),
+        })
+        .collect()
+}
+
+// arquivo de relatorio, que precisa terminar em .csv ou .json
+fn parse_report_path(option: &str, value: String) -> Result<PathBuf, CliError> {
+    let path = PathBuf::from(value);
+    match ReportFormat::from_path(&path) {
+        Some(_) => Ok(path),
+        None => Err(CliError::InvalidValue { option: option.to_string(), value: path.display().to_string() }),
+    }
+}
+
+// quantidades de threads padrao da varredura: 1, 2, 4, ... ate cores, e o proprio cores
+fn sweep_threads(cores: usize) -> Vec<usize> {
+    let mut threads: Vec<usize> = (0..).map(|n| 1 << n).take_while(|&t| t < cores).collect();
+    threads.push(cores);
+    threads
+}
+
+// le os argumentos (sem o nome do programa)
+// as opcoes aceitam o valor no argumento seguinte ("--rows 50") ou depois de um igual ("--rows=50")
+pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Options, CliError> {
+    let mut options = Options::default();
+    let mut command = None;
+    let mut threads_given = false;
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        let (name, inline) = match arg.split_once('=') {
+            Some((name, value)) if name.starts_with('-') => (name.to_string(), Some(value.to_string())),
+            _ => (arg.clone(), None),
+        };
+        // pega o valor da opcao, que pode estar no mesmo argumento ou no proximo
+        let mut value = || match inline.clone() {
+            Some(value) => Ok(value),
+            None => args.next().ok_or_else(|| CliError::MissingValue(name.clone())),
+        };
+
+        match name.as_str() {
+            "-h" | "--help" => command = Some(Command::Help),
+            "-r" | "--rows" => options.rows = parse_positive(&name, &value()?)?,
+            "-c" | "--cols" => options.cols = parse_positive(&name, &value()?)?,
+            "-n" | "--size" => {
+                let size = parse_positive(&name, &value()?)?;
+                options.rows = size;
+                options.cols = size;
+            }
+            "--min" => options.min = parse_number(&name, &value()?)?,
+            "--max" => options.max = parse_number(&name, &value()?)?,
+            "-t" | "--threads" => {
+                options.threads = parse_threads(&name, &value()?)?;
+                threads_given = true;
+            }
+            "--sizes" => {
+                let value = value()?;
+                options.sizes = value.split(',').map(|size| parse_positive(&name, size.trim())).collect::<Result<_, _>>()?;
+            }
+            "--pool-size" => {
+                let value = value()?;
+                options.pool_size = Some(if value.trim() == "auto" { pool::AUTO } else { parse_number(&name, &value)? });
+            }
+            "--warmup" => options.bench.warmup = parse_number(&name, &value()?)?,
+            "--reps" => options.bench.repetitions = parse_positive(&name, &value()?)?,
+            "-s" | "--seed" => options.seed = Some(parse_number(&name, &value()?)?),
+            "-a" | "--left" => options.left = Some(PathBuf::from(value()?)),
+            "-b" | "--right" => options.right = Some(PathBuf::from(value()?)),
+            "-o" | "--output" => options.output = Some(PathBuf::from(value()?)),
+            "--report" => options.report = Some(parse_report_path(&name, value()?)?),
+            "--compare" => options.compare = Some(parse_report_path(&name, value()?)?),
+            "--max-regression" => {
+                let value = value()?;
+                options.max_regression = parse_number(&name, &value)?;
+                if options.max_regression.is_nan() || options.max_regression < 0.0 {
+                    return Err(CliError::InvalidValue { option: name, value });
+                }
+            }
+            "--life" => options.life = true,
+            "all" | "sum" | "sub" | "mul" | "sweep" if command.is_none() => {
+                command = Some(match name.as_str() {
+                    "all" => Command::All,
+                    "sum" => Command::Sum,
+                    "sub" => Command::Sub,
+                    "mul" => Command::Mul,
+                    _ => Command::Sweep,
+                });
+            }
+            _ => return Err(CliError::Unknown(arg)),
+        }
+    }
+    options.command = command.unwrap_or(Command::All);
+    if options.command == Command::Help {
+        return Ok(options);
+    }
+
+    if options.min >= options.max {
+        return Err(CliError::Conflict("--min must be smaller than --max"));
+    }
+    if options.left.is_some() != options.right.is_some() {
+        return Err(CliError::Conflict("--left and --right must be given together"));
+    }
+    if options.compare.is_some() && options.command == Command::Sweep {
+        return Err(CliError::Conflict("--compare needs one of the commands all, sum, sub or mul"));
+    }
+    if options.command == Command::Sweep && !threads_given {
+        options.threads = sweep_threads(pool::default_size());
+    }
+    if options.output.is_some() && !matches!(options.command, Command::Sum | Command::Sub | Command::Mul) {
+        return Err(CliError::Conflict("--output needs a single operation: sum, sub or mul"));
+    }
+    Ok(options)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_args(args: &[&str]) -> Result<Options, CliError> {
+        parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn defaults_and_values() -> Result<(), CliError> {
+        assert_eq!(parse_args(&[])?, Options::default());
+
+        // o valor pode vir no argumento seguinte ou depois de um igual
+        let options = parse_args(&["sum", "--rows", "50", "-c=7", "-t", "1,auto, 4", "--seed=9", "--reps", "3", "--life"])?;
+        assert_eq!(options.command, Command::Sum);
+        assert_eq!((options.rows, options.cols), (50, 7));
+        assert_eq!(options.threads, vec![1, pool::AUTO, 4]);
+        assert_eq!(options.seed, Some(9));
+        assert_eq!(options.bench.repetitions, 3);
+        assert!(options.life);
+
+        let options = parse_args(&["mul", "-n", "12", "-a", "a.csv", "-b", "b.mtx", "-o", "c.bin", "--report", "r.json"])?;
+        assert_eq!((options.rows, options.cols), (12, 12));
+        assert_eq!(options.output, Some(PathBuf::from("c.bin")));
+        assert_eq!(options.report, Some(PathBuf::from("r.json")));
+        Ok(())
+    }
+
+    // --help nao valida as outras opcoes
+    #[test]
+    fn help_skips_the_checks() -> Result<(), CliError> {
+        assert_eq!(parse_args(&["--min", "5", "--max", "1", "-h"])?.command, Command::Help);
+        assert_eq!(parse_args(&["sweep", "--help"])?.command, Command::Help);
+        Ok(())
+    }
+
+    #[test]
+    fn sweep_and_pool_threads() -> Result<(), CliError> {
+        assert_eq!(sweep_threads(1), vec![1]);
+        assert_eq!(sweep_threads(6), vec![1, 2, 4, 6]);
+        assert_eq!(sweep_threads(8), vec![1, 2, 4, 8]);
+        assert_eq!(parse_args(&["sweep"])?.threads, sweep_threads(pool::default_size()));
+        assert_eq!(parse_args(&["sweep", "-t", "3"])?.threads, vec![3]);
+
+        assert_eq!(parse_args(&["--pool-size", "3", "-t", "100"])?.pool_threads(), 3);
+        assert_eq!(parse_args(&["-t", "1,100"])?.pool_threads(), pool::default_size().max(100));
+        Ok(())
+    }
+
+    #[test]
+    fn errors_and_messages() {
+        let unknown = parse_args(&["--bogus"]).unwrap_err();
+        assert_eq!(unknown, CliError::Unknown("--bogus".to_string()));
+        assert_eq!(unknown.to_string(), "unknown argument \"--bogus\"");
+        // so um comando por execucao
+        assert_eq!(parse_args(&["sum", "mul"]), Err(CliError::Unknown("mul".to_string())));
+
+        let missing = parse_args(&["sum", "--rows"]).unwrap_err();
+        assert_eq!(missing, CliError::MissingValue("--rows".to_string()));
+        assert_eq!(missing.to_string(), "missing value for --rows");
+
+        let invalid = |option: &str, value: &str| Err(CliError::InvalidValue { option: option.to_string(), value: value.to_string() });
+        assert_eq!(parse_args(&["--rows", "0"]), invalid("--rows", "0"));
+        assert_eq!(parse_args(&["-c=x"]), invalid("-c", "x"));
+        assert_eq!(parse_args(&["--sizes", "10,-1"]), invalid("--sizes", "-1"));
+        assert_eq!(parse_args(&["--report", "out.txt"]), invalid("--report", "out.txt"));
+        assert_eq!(parse_args(&["--max-regression", "-1"]), invalid("--max-regression", "-1"));
+        assert_eq!(parse_args(&["--max-regression=NaN"]), invalid("--max-regression", "NaN"));
+        assert_eq!(parse_args(&["-t", "2,x"]).unwrap_err().to_string(), "invalid value \"x\" for -t");
+    }
+
+    // opcoes que fazem sentido sozinhas, mas nao juntas
+    #[test]
+    fn conflicting_options() {
+        let conflict = |args: &[&str]| match parse_args(args) {
+            Err(CliError::Conflict(message)) => message,
+            other => panic!("expected a conflict for {:?}, got {:?}", args, other),
+        };
+        assert_eq!(conflict(&["--min", "3", "--max", "3"]), "--min must be smaller than --max");
+        assert_eq!(conflict(&["sum", "--left", "a.csv"]), "--left and --right must be given together");
+        assert_eq!(conflict(&["sweep", "--compare", "base.json"]), "--compare needs one of the commands all, sum, sub or mul");
+        assert_eq!(conflict(&["-o", "c.csv"]), "--output needs a single operation: sum, sub or mul");
+        assert_eq!(conflict(&["sweep", "-o", "c.csv"]), "--output needs a single operation: sum, sub or mul");
+    }
+
+    // erro nos argumentos e erro ao rodar saem com codigos diferentes
+    #[test]
+    fn exit_codes() {
+        assert_eq!((EXIT_USAGE, EXIT_FAILURE), (2, 1));
+    }
+}
@@ -0,0 +1,613 @@
+use crate::cholesky::check_symmetric;
+use crate::matrix::{Matrix, MatrixError};
+use crate::ops::{check_square, split_rows_mut};
+use crate::pool;
+
+// tolerancia relativa dos metodos iterativos
+pub const DEFAULT_TOLERANCE: f64 = 1e-10;
+// iteracoes da iteracao de potencia antes de desistir
+pub const DEFAULT_MAX_ITERATIONS: usize = 1000;
+// varreduras de Jacobi antes de desistir, na pratica bastam menos de 10
+const MAX_SWEEPS: usize = 50;
+// a decomposicao de Jacobi para quando os elementos fora da diagonal somam menos que isso vezes a matriz inteira
+const JACOBI_TOLERANCE: f64 = 1e-14;
+
+// um autovalor e o seu autovetor, com tamanho 1
+#[derive(Debug, Clone, PartialEq)]
+pub struct Eigenpair {
+    pub value: f64,
+    pub vector: Vec<f64>,
+}
+
+// decomposicao de uma matriz simetrica: A = V * diag(values) * V^T
+#[derive(Debug, Clone, PartialEq)]
+pub struct SymmetricEigen {
+    // autovalores do maior para o menor
+    pub values: Vec<f64>,
+    // a coluna i e o autovetor do autovalor values[i], as colunas sao ortonormais
+    pub vectors: Matrix<f64>,
+}
+
+// decomposicao em valores singulares: A = U * diag(singular_values) * V^T, na forma reduzida
+// com A m x n e k = min(m, n), U e m x k e V e n x k
+#[derive(Debug, Clone, PartialEq)]
+pub struct Svd {
+    pub u: Matrix<f64>,
+    // valores singulares do maior para o menor, todos maiores ou iguais a zero
+    pub singular_values: Vec<f64>,
+    pub v: Matrix<f64>,
+}
+
+// maior elemento da matriz em valor absoluto
+fn max_abs(a: &Matrix<f64>) -> f64 {
+    a.as_slice().iter().fold(0.0, |m: f64, x| m.max(x.abs()))
+}
+
+// soma dos quadrados dos elementos fora da diagonal
+fn off_diagonal(a: &Matrix<f64>) -> f64 {
+    let n = a.rows();
+    (0..n).map(|i| (0..n).filter(|&j| j != i).map(|j| a[(i, j)].powi(2)).sum::<f64>()).sum()
+}
+
+fn dot(x: &[f64], y: &[f64]) -> f64 {
+    x.iter().zip(y).map(|(a, b)| a * b).sum()
+}
+
+fn norm(x: &[f64]) -> f64 {
+    dot(x, x).sqrt()
+}
+
+// vetor inicial dos metodos iterativos, com todos os elementos diferentes para nao ser ortogonal
+// ao autovetor procurado em matrizes com estrutura regular; fixo para o resultado ser reproduzivel
+fn start_vector(n: usize) -> Vec<f64> {
+    let v: Vec<f64> = (0..n).map(|i| 1.0 + i as f64 / n as f64).collect();
+    let length = norm(&v);
+    v.into_iter().map(|x| x / length).collect()
+}
+
+// a * x, com as linhas da saida divididas entre as threads
+fn mul_vector(a: &Matrix<f64>, x: &[f64], thr: usize) -> Vec<f64> {
+    let n = a.rows();
+    let mut y = vec![0.0; n];
+    pool::global().scope(|s| {
+        for (start, chunk) in split_rows_mut(&mut y, n, 1, thr) {
+            s.spawn(move || {
+                for (r, out) in chunk.iter_mut().enumerate() {
+                    *out = dot(a.row(start + r), x);
+                }
+            });
+        }
+    });
+    y
+}
+
+// iteracao de potencia: multiplica um vetor por a ate ele parar de mudar de direcao,
+// convergindo para o autovalor de maior valor absoluto; o autovalor e o quociente de Rayleigh x^T * a * x
+// para quando |a * x - value * x| fica abaixo de tol vezes |value|
+// se os dois maiores autovalores tem o mesmo valor absoluto (por exemplo 1 e -1) ele nao converge
+pub fn power_iteration(a: &Matrix<f64>, tol: f64, max_iterations: usize, thr: usize) -> Result<Eigenpair, MatrixError> {
+    check_square(a, "power_iteration")?;
+    let mut x = start_vector(a.rows());
+    for _ in 0..max_iterations {
+        let y = mul_vector(a, &x, thr);
+        let value = dot(&x, &y);
+        let residual = norm(&y.iter().zip(&x).map(|(yi, xi)| yi - value * xi).collect::<Vec<_>>());
+        if residual <= tol * value.abs() {
+            return Ok(Eigenpair { value, vector: x });
+        }
+        let length = norm(&y);
+        if length == 0.0 {
+            // a * x = 0, x ja e autovetor do autovalor zero
+            return Ok(Eigenpair { value: 0.0, vector: x });
+        }
+        x = y.into_iter().map(|v| v / length).collect();
+    }
+    Err(MatrixError::NoConvergence { op: "power_iteration", iterations: max_iterations })
+}
+
+// Lanczos: monta uma base ortonormal do subespaco de Krylov (x, a * x, a^2 * x, ...) com steps vetores,
+// onde a fica tridiagonal, e retorna os autovalores dessa tridiagonal do maior para o menor
+// os extremos convergem primeiro, entao poucos passos ja dao os maiores e menores autovalores de a;
+// com steps = n sao todos os autovalores
+// cada vetor novo e reortogonalizado contra todos os anteriores, senao o arredondamento faz autovalores repetirem
+pub fn lanczos(a: &Matrix<f64>, steps: usize, thr: usize) -> Result<Vec<f64>, MatrixError> {
+    check_square(a, "lanczos")?;
+    check_symmetric(a, max_abs(a))?;
+    let n = a.rows();
+    let steps = steps.clamp(1, n);
+    let mut basis = vec![start_vector(n)];
+    let mut alphas = Vec::with_capacity(steps);
+    let mut betas = Vec::with_capacity(steps);
+
+    for j in 0..steps {
+        let q = &basis[j];
+        let mut w = mul_vector(a, q, thr);
+        let alpha = dot(q, &w);
+        alphas.push(alpha);
+        if j + 1 == steps {
+            break;
+        }
+        for v in &basis {
+            let projection = dot(v, &w);
+            w.iter_mut().zip(v).for_each(|(wi, vi)| *wi -= projection * vi);
+        }
+        let beta = norm(&w);
+        // o subespaco ficou invariante, os autovalores da tridiagonal ja sao autovalores exatos de a
+        if beta <= DEFAULT_TOLERANCE * max_abs(a) {
+            break;
+        }
+        betas.push(beta);
+        basis.push(w.into_iter().map(|x| x / beta).collect());
+    }
+
+    let k = alphas.len();
+    let mut t = Matrix::new(k, k);
+    for i in 0..k {
+        t[(i, i)] = alphas[i];
+        if i + 1 < k {
+            t[(i, i + 1)] = betas[i];
+            t[(i + 1, i)] = betas[i];
+        }
+    }
+    Ok(symmetric_eigen_sequential(&t)?.values)
+}
+
+// pares (p, q) de cada rodada de um torneio round robin: nas rodadas os pares nao tem indice em comum,
+// entao as rotacoes de uma rodada podem ser aplicadas ao mesmo tempo, e em todas as rodadas cada par aparece uma vez
+// com n impar entra um indice a mais, e quem cai com ele fica fora da rodada
+fn rotation_sets(n: usize) -> Vec<Vec<(usize, usize)>> {
+    let m = n + n % 2;
+    let mut players: Vec<usize> = (0..m).collect();
+    let mut rounds = Vec::with_capacity(m.saturating_sub(1));
+    for _ in 1..m {
+        let pairs = (0..m / 2)
+            .map(|i| (players[i], players[m - 1 - i]))
+            .filter(|&(p, q)| p < n && q < n)
+            .map(|(p, q)| (p.min(q), p.max(q)))
+            .collect();
+        rounds.push(pairs);
+        // o primeiro fica parado e os outros giram uma posicao
+        let last = players.pop().unwrap();
+        players.insert(1, last);
+    }
+    rounds
+}
+
+// rotacao de Jacobi nos indices p e q, com cosseno c e seno s
+#[derive(Debug, Clone, Copy)]
+struct Rotation {
+    p: usize,
+    q: usize,
+    c: f64,
+    s: f64,
+}
+
+// rotacao que zera o elemento (p, q) de a, ou None se ele ja e zero
+fn rotation(a: &Matrix<f64>, p: usize, q: usize) -> Option<Rotation> {
+    let apq = a[(p, q)];
+    if apq == 0.0 {
+        return None;
+    }
+    let theta = (a[(q, q)] - a[(p, p)]) / (2.0 * apq);
+    // a menor das duas raizes de t^2 + 2 * theta * t - 1 = 0, que e a rotacao de menor angulo
+    let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+    let c = 1.0 / (t * t + 1.0).sqrt();
+    Some(Rotation { p, q, c, s: t * c })
+}
+
+// multiplica as linhas do pedaco pelas rotacoes pela direita (M * J), misturando as colunas p e q de cada linha
+fn rotate_columns(rows: &mut [f64], n: usize, rotations: &[Rotation]) {
+    for row in rows.chunks_mut(n) {
+        for r in rotations {
+            let (x, y) = (row[r.p], row[r.q]);
+            row[r.p] = r.c * x - r.s * y;
+            row[r.q] = r.s * x + r.c * y;
+        }
+    }
+}
+
+// linhas start, start + 1, ... de J^T * b: as linhas p e q de cada rotacao sao misturadas, as outras so copiadas
+// partner[i] e a rotacao que mexe na linha i, se existir
+fn rotate_rows(b: &Matrix<f64>, start: usize, out: &mut [f64], partner: &[Option<Rotation>]) {
+    let n = b.cols();
+    for (r, row) in out.chunks_mut(n).enumerate() {
+        let i = start + r;
+        match partner[i] {
+            None => row.copy_from_slice(b.row(i)),
+            Some(rot) => {
+                let (bp, bq) = (b.row(rot.p), b.row(rot.q));
+                for j in 0..n {
+                    row[j] = if i == rot.p { rot.c * bp[j] - rot.s * bq[j] } else { rot.s * bp[j] + rot.c * bq[j] };
+                }
+            }
+        }
+    }
+}
+
+// aplica as linhas das partes em paralelo com split_rows_mut, ou tudo de uma vez sem threads
+// out e uma matriz rows x cols em ordem de linhas
+fn for_rows<T: Send>(out: &mut [T], rows: usize, cols: usize, thr: Option<usize>, f: impl Fn(usize, &mut [T]) + Sync) {
+    match thr {
+        None => f(0, out),
+        Some(thr) => {
+            let f = &f;
+            pool::global().scope(|s| {
+                for (start, chunk) in split_rows_mut(out, rows, cols, thr) {
+                    s.spawn(move || f(start, chunk));
+                }
+            });
+        }
+    }
+}
+
+// Jacobi ciclico: cada rotacao A' = J^T * A * J zera um elemento fora da diagonal, e as varreduras
+// repetem todos os pares ate a matriz ficar diagonal; os autovetores sao o produto das rotacoes
+// as rotacoes de uma mesma rodada do round robin nao tem indices em comum, entao sao aplicadas juntas:
+// primeiro A * J e V * J, onde cada linha e independente, depois J^T * (A * J) em outra matriz,
+// onde cada linha depende so de duas linhas da anterior; com Some(thr) as linhas sao divididas entre as threads
+// desiste com NoConvergence se depois de max_sweeps varreduras a matriz ainda nao e diagonal
+fn jacobi(a: &Matrix<f64>, thr: Option<usize>, max_sweeps: usize) -> Result<SymmetricEigen, MatrixError> {
+    check_square(a, "symmetric_eigen")?;
+    let scale = max_abs(a);
+    check_symmetric(a, scale)?;
+    let n = a.rows();
+    let tolerance = JACOBI_TOLERANCE * JACOBI_TOLERANCE * a.as_slice().iter().map(|x| x * x).sum::<f64>();
+    let sets = rotation_sets(n);
+
+    let mut current = a.clone();
+    let mut next = Matrix::new(n, n);
+    let mut vectors = Matrix::new(n, n);
+    for i in 0..n {
+        vectors[(i, i)] = 1.0;
+    }
+
+    for _ in 0..max_sweeps {
+        if off_diagonal(&current) <= tolerance {
+            break;
+        }
+        for set in &sets {
+            let rotations: Vec<Rotation> = set.iter().filter_map(|&(p, q)| rotation(&current, p, q)).collect();
+            if rotations.is_empty() {
+                continue;
+            }
+            let mut partner = vec![None; n];
+            for r in &rotations {
+                partner[r.p] = Some(*r);
+                partner[r.q] = Some(*r);
+            }
+            let rotations = &rotations;
+            for_rows(current.as_mut_slice(), n, n, thr, |_, rows| rotate_columns(rows, n, rotations));
+            for_rows(vectors.as_mut_slice(), n, n, thr, |_, rows| rotate_columns(rows, n, rotations));
+            let (source, partner) = (&current, &partner);
+            for_rows(next.as_mut_slice(), n, n, thr, |start, rows| rotate_rows(source, start, rows, partner));
+            std::mem::swap(&mut current, &mut next);
+        }
+    }
+    // confere de novo depois do laco, pois a ultima varredura pode ter deixado a matriz diagonal
+    if off_diagonal(&current) > tolerance {
+        return Err(MatrixError::NoConvergence { op: "symmetric_eigen", iterations: max_sweeps });
+    }
+
+    // ordena do maior para o menor autovalor, levando as colunas de V junto
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|&i, &j| current[(j, j)].total_cmp(&current[(i, i)]));
+    let values = order.iter().map(|&i| current[(i, i)]).collect();
+    let mut sorted = Matrix::new(n, n);
+    for (col, &i) in order.iter().enumerate() {
+        for row in 0..n {
+            sorted[(row, col)] = vectors[(row, i)];
+        }
+    }
+    Ok(SymmetricEigen { values, vectors: sorted })
+}
+
+// todos os autovalores e autovetores de uma matriz simetrica, por rotacoes de Jacobi
+pub fn symmetric_eigen_sequential(a: &Matrix<f64>) -> Result<SymmetricEigen, MatrixError> {
+    jacobi(a, None, MAX_SWEEPS)
+}
+
+// o mesmo que symmetric_eigen_sequential, com as rotacoes de cada rodada aplicadas usando threads
+pub fn symmetric_eigen_threads(a: &Matrix<f64>, thr: usize) -> Result<SymmetricEigen, MatrixError> {
+    jacobi(a, Some(thr), MAX_SWEEPS)
+}
+
+// rotacao que deixa as linhas p e q de w ortogonais (Jacobi de um lado so), ou None se elas ja sao
+// ortogonais ate JACOBI_TOLERANCE, relativo aos tamanhos das duas
+// linhas com tamanho ao quadrado ate negligible sao so arredondamento de uma coluna que seria zero:
+// gira-las nunca termina, entao contam como ja ortogonais
+fn orthogonalizing_rotation(w: &Matrix<f64>, p: usize, q: usize, negligible: f64) -> Option<Rotation> {
+    let (wp, wq) = (w.row(p), w.row(q));
+    let (alpha, beta, gamma) = (dot(wp, wp), dot(wq, wq), dot(wp, wq));
+    if alpha.min(beta) <= negligible || gamma.abs() <= JACOBI_TOLERANCE * (alpha * beta).sqrt() {
+        return None;
+    }
+    // o mesmo angulo que zera o elemento (p, q) de W * W^T, sem formar o produto
+    let zeta = (beta - alpha) / (2.0 * gamma);
+    let t = zeta.signum() / (zeta.abs() + (zeta * zeta + 1.0).sqrt());
+    let c = 1.0 / (t * t + 1.0).sqrt();
+    Some(Rotation { p, q, c, s: t * c })
+}
+
+// completa as colunas de U que ficaram sem direcao (valor singular zero) por Gram-Schmidt:
+// de todos os vetores da base canonica, usa o que sobra mais depois de tirar as colunas ja prontas
+// columns sao as colunas de U, guardadas como linhas de tamanho m
+fn complete_orthonormal(columns: &mut [Vec<f64>], done: &mut [bool]) {
+    let m = columns[0].len();
+    for k in 0..columns.len() {
+        if done[k] {
+            continue;
+        }
+        let mut best = vec![0.0; m];
+        let mut best_norm = 0.0;
+        for j in 0..m {
+            let mut v = vec![0.0; m];
+            v[j] = 1.0;
+            // duas passadas de Gram-Schmidt, a segunda corrige o arredondamento da primeira
+            for _ in 0..2 {
+                for (column, _) in columns.iter().zip(done.iter()).filter(|(_, &d)| d) {
+                    let projection = dot(column, &v);
+                    v.iter_mut().zip(column).for_each(|(x, u)| *x -= projection * u);
+                }
+            }
+            let length = norm(&v);
+            if length > best_norm {
+                best = v;
+                best_norm = length;
+            }
+        }
+        columns[k] = best.into_iter().map(|x| x / best_norm).collect();
+        done[k] = true;
+    }
+}
+
+// SVD por Jacobi de um lado so (Hestenes), direto sobre A, com m >= n: rotacoes pela direita deixam as colunas
+// de A * V ortogonais duas a duas; no fim os valores singulares sao os tamanhos dessas colunas
+// e cada coluna de U e a coluna dividida pelo seu tamanho
+// sem formar A^T * A o condicionamento nao e elevado ao quadrado, e os valores singulares pequenos mantem a precisao
+// as colunas sao guardadas como linhas de W = (A * V)^T, entao as rotacoes de uma rodada do round robin
+// sao aplicadas com rotate_rows, com as linhas divididas entre as threads com Some(thr)
+// matrizes com mais colunas que linhas usam a SVD da transposta, trocando U e V
+// desiste com NoConvergence se depois de max_sweeps varreduras ainda ha colunas para girar
+fn svd(a: &Matrix<f64>, thr: Option<usize>, max_sweeps: usize) -> Result<Svd, MatrixError> {
+    if a.is_empty() {
+        return Err(MatrixError::Empty);
+    }
+    if a.rows() < a.cols() {
+        let t = svd(&a.transpose(), thr, max_sweeps)?;
+        return Ok(Svd { u: t.v, singular_values: t.singular_values, v: t.u });
+    }
+    let (m, n) = a.shape();
+    let sets = rotation_sets(n);
+    // colunas com tamanho ate m * epsilon * |A| (norma de Frobenius) sao tratadas como zero
+    let cutoff = m as f64 * f64::EPSILON * a.as_slice().iter().map(|x| x * x).sum::<f64>().sqrt();
+
+    let mut w = a.transpose();
+    let mut w_next = Matrix::new(n, m);
+    // V^T, que recebe as mesmas rotacoes nas linhas
+    let mut vt = Matrix::new(n, n);
+    let mut vt_next = Matrix::new(n, n);
+    for i in 0..n {
+        vt[(i, i)] = 1.0;
+    }
+
+    let mut converged = false;
+    for _ in 0..max_sweeps {
+        let mut rotated = false;
+        for set in &sets {
+            let mut found = vec![None; set.len()];
+            let source = &w;
+            for_rows(&mut found, set.len(), 1, thr, |start, out| {
+                for (r, &(p, q)) in out.iter_mut().zip(&set[start..]) {
+                    *r = orthogonalizing_rotation(source, p, q, cutoff * cutoff);
+                }
+            });
+            let rotations: Vec<Rotation> = found.into_iter().flatten().collect();
+            if rotations.is_empty() {
+                continue;
+            }
+            rotated = true;
+            let mut partner = vec![None; n];
+            for r in &rotations {
+                partner[r.p] = Some(*r);
+                partner[r.q] = Some(*r);
+            }
+            let (w_source, vt_source, partner) = (&w, &vt, &partner);
+            for_rows(w_next.as_mut_slice(), n, m, thr, |start, rows| rotate_rows(w_source, start, rows, partner));
+            for_rows(vt_next.as_mut_slice(), n, n, thr, |start, rows| rotate_rows(vt_source, start, rows, partner));
+            std::mem::swap(&mut w, &mut w_next);
+            std::mem::swap(&mut vt, &mut vt_next);
+        }
+        if !rotated {
+            converged = true;
+            break;
+        }
+    }
+    // a ultima varredura pode ter deixado as colunas ortogonais, entao confere se ainda falta alguma rotacao
+    let pending = || sets.iter().flatten().any(|&(p, q)| orthogonalizing_rotation(&w, p, q, cutoff * cutoff).is_some());
+    if !converged && pending() {
+        return Err(MatrixError::NoConvergence { op: "svd", iterations: max_sweeps });
+    }
+
+    // ordena do maior para o menor valor singular, levando as linhas de W e de V^T junto
+    let lengths: Vec<f64> = (0..n).map(|i| norm(w.row(i))).collect();
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|&i, &j| lengths[j].total_cmp(&lengths[i]));
+    let singular_values: Vec<f64> = order.iter().map(|&i| lengths[i]).collect();
+
+    // colunas de tamanho no nivel do arredondamento nao tem direcao confiavel, sao trocadas por Gram-Schmidt
+    let mut done: Vec<bool> = singular_values.iter().map(|&sigma| sigma > cutoff).collect();
+    let mut columns: Vec<Vec<f64>> = order
+        .iter()
+        .zip(&done)
+        .map(|(&i, &ok)| if ok { w.row(i).iter().map(|x| x / lengths[i]).collect() } else { vec![0.0; m] })
+        .collect();
+    if done.iter().any(|&ok| !ok) {
+        complete_orthonormal(&mut columns, &mut done);
+    }
+
+    let mut u = Matrix::new(m, n);
+    let mut v = Matrix::new(n, n);
+    for (col, (column, &i)) in columns.iter().zip(&order).enumerate() {
+        for row in 0..m {
+            u[(row, col)] = column[row];
+        }
+        for row in 0..n {
+            v[(row, col)] = vt[(i, row)];
+        }
+    }
+    Ok(Svd { u, singular_values, v })
+}
+
+// SVD sequencial
+pub fn svd_sequential(a: &Matrix<f64>) -> Result<Svd, MatrixError> {
+    svd(a, None, MAX_SWEEPS)
+}
+
+// SVD com as rotacoes de cada rodada calculadas e aplicadas usando threads
+pub fn svd_threads(a: &Matrix<f64>, thr: usize) -> Result<Svd, MatrixError> {
+    svd(a, Some(thr), MAX_SWEEPS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::numeric::approx_eq;
+    use crate::ops::{mul_matrix_sequential, sum_matrix_sequential};
+    use crate::random::{create_f64_matrix_from_random, create_spd_matrix_from_random};
+    use crate::semiring::{identity, Standard};
+    use crate::testing::{assert_threads_match, LINALG_TOLERANCE};
+
+    // reconstroi a matriz a partir de colunas e valores: left * diag(values) * right^T
+    fn reconstruct(left: &Matrix<f64>, values: &[f64], right: &Matrix<f64>) -> Result<Matrix<f64>, MatrixError> {
+        let mut scaled = left.clone();
+        for row in scaled.as_mut_slice().chunks_mut(values.len()) {
+            row.iter_mut().zip(values).for_each(|(x, v)| *x *= v);
+        }
+        mul_matrix_sequential(&scaled, &right.transpose())
+    }
+
+    // colunas ortonormais: m^T * m = I
+    fn assert_orthonormal(m: &Matrix<f64>) -> Result<(), MatrixError> {
+        assert!(approx_eq(&mul_matrix_sequential(&m.transpose(), m)?, &identity::<Standard<f64>>(m.cols()), LINALG_TOLERANCE));
+        Ok(())
+    }
+
+    // simetrica com autovalores positivos e negativos, n impar para ter um indice fora de cada rodada
+    fn symmetric(n: usize) -> Result<Matrix<f64>, MatrixError> {
+        let b = create_f64_matrix_from_random(n, n, -10, 10);
+        sum_matrix_sequential(&b, &b.transpose())
+    }
+
+    #[test]
+    fn jacobi_symmetric_eigen() -> Result<(), MatrixError> {
+        let n = 31;
+        let sym = symmetric(n)?;
+        let eig = symmetric_eigen_sequential(&sym)?;
+        assert_threads_match(Ok(eig.clone()), |thr| symmetric_eigen_threads(&sym, thr));
+        assert!(eig.values.windows(2).all(|w| w[0] >= w[1]));
+        assert!(eig.values[0] > 0.0 && eig.values[n - 1] < 0.0);
+        assert_orthonormal(&eig.vectors)?;
+        assert!(approx_eq(&reconstruct(&eig.vectors, &eig.values, &eig.vectors)?, &sym, LINALG_TOLERANCE));
+        let trace: f64 = (0..n).map(|i| sym[(i, i)]).sum();
+        assert!((eig.values.iter().sum::<f64>() - trace).abs() < LINALG_TOLERANCE * eig.values[0]);
+
+        let ritz = lanczos(&sym, n, 3)?;
+        assert_eq!(ritz.len(), n);
+        assert!(ritz.iter().zip(&eig.values).all(|(r, e)| (r - e).abs() < LINALG_TOLERANCE * eig.values[0]));
+        assert!(matches!(symmetric_eigen_sequential(&create_f64_matrix_from_random(4, 4, 1, 10)), Err(MatrixError::NotSymmetric { .. })));
+        Ok(())
+    }
+
+    // o maior autovalor pela iteracao de potencia e por Lanczos
+    #[test]
+    fn dominant_eigenvalue() -> Result<(), MatrixError> {
+        let spd = create_spd_matrix_from_random(40, 10);
+        let all = symmetric_eigen_threads(&spd, 3)?.values;
+        let dominant = power_iteration(&spd, DEFAULT_TOLERANCE, DEFAULT_MAX_ITERATIONS, 3)?;
+        assert!((dominant.value / all[0] - 1.0).abs() < LINALG_TOLERANCE);
+        let av = mul_matrix_sequential(&spd, &Matrix::from_vec(40, 1, dominant.vector.clone())?)?;
+        let lv = Matrix::from_vec(40, 1, dominant.vector.iter().map(|x| x * dominant.value).collect())?;
+        assert!(approx_eq(&av, &lv, LINALG_TOLERANCE));
+        assert!((lanczos(&spd, 15, 3)?[0] / all[0] - 1.0).abs() < LINALG_TOLERANCE);
+
+        // autovalores 1 e -1 tem o mesmo tamanho, a iteracao de potencia fica trocando entre os dois
+        let swap = Matrix::try_from(vec![vec![0.0, 1.0], vec![1.0, 0.0]])?;
+        assert!(matches!(power_iteration(&swap, DEFAULT_TOLERANCE, 100, 1), Err(MatrixError::NoConvergence { .. })));
+        Ok(())
+    }
+
+    // SVD de uma matriz alta e de uma larga
+    #[test]
+    fn svd_tall_and_wide() -> Result<(), MatrixError> {
+        for (rows, cols) in [(50, 20), (20, 50)] {
+            let a = create_f64_matrix_from_random(rows, cols, -10, 10);
+            let svd = svd_sequential(&a)?;
+            assert_threads_match(Ok(svd.clone()), |thr| svd_threads(&a, thr));
+            let k = rows.min(cols);
+            assert_eq!((svd.u.shape(), svd.singular_values.len(), svd.v.shape()), ((rows, k), k, (cols, k)));
+            assert!(svd.singular_values.windows(2).all(|w| w[0] >= w[1]) && svd.singular_values[k - 1] >= 0.0);
+            assert!(approx_eq(&reconstruct(&svd.u, &svd.singular_values, &svd.v)?, &a, LINALG_TOLERANCE));
+            assert_orthonormal(&svd.u)?;
+            assert_orthonormal(&svd.v)?;
+        }
+        Ok(())
+    }
+
+    // posto menor que o numero de colunas: os valores singulares que sobram sao zero,
+    // e U continua com colunas ortonormais, completadas por Gram-Schmidt
+    #[test]
+    fn svd_rank_deficient() -> Result<(), MatrixError> {
+        for (rows, cols, rank) in [(40, 12, 5), (12, 40, 5), (30, 10, 1), (8, 8, 0)] {
+            let left = create_f64_matrix_from_random(rows, rank.max(1), -10, 10);
+            let right = create_f64_matrix_from_random(rank.max(1), cols, -10, 10);
+            let a = if rank == 0 { Matrix::new(rows, cols) } else { mul_matrix_sequential(&left, &right)? };
+            let svd = svd_sequential(&a)?;
+            assert_threads_match(Ok(svd.clone()), |thr| svd_threads(&a, thr));
+            let k = rows.min(cols);
+            let scale = svd.singular_values[0].max(1.0);
+            assert!(svd.singular_values[rank..].iter().all(|&sigma| sigma < LINALG_TOLERANCE * scale));
+            assert!(svd.singular_values[..rank].iter().all(|&sigma| sigma > LINALG_TOLERANCE * scale));
+            assert_eq!(svd.u.shape(), (rows, k));
+            assert!(approx_eq(&reconstruct(&svd.u, &svd.singular_values, &svd.v)?, &a, LINALG_TOLERANCE * scale));
+            assert_orthonormal(&svd.u)?;
+            assert_orthonormal(&svd.v)?;
+        }
+        Ok(())
+    }
+
+    // uma unica rotacao deixa uma matriz 2 x 2 diagonal (e as colunas ortogonais), entao uma varredura basta:
+    // a convergencia e conferida depois da ultima varredura, e nao so no comeco de cada uma
+    #[test]
+    fn converges_on_the_last_sweep() -> Result<(), MatrixError> {
+        let a = Matrix::try_from(vec![vec![2.0, 1.0], vec![1.0, 2.0]])?;
+        let eig = jacobi(&a, None, 1)?;
+        assert!(eig.values.iter().zip([3.0, 1.0]).all(|(value, expected)| (value - expected).abs() < LINALG_TOLERANCE));
+        assert_eq!(jacobi(&a, None, 0), Err(MatrixError::NoConvergence { op: "symmetric_eigen", iterations: 0 }));
+
+        let b = Matrix::try_from(vec![vec![3.0, 1.0], vec![1.0, 2.0], vec![0.0, 1.0]])?;
+        let decomposition = svd(&b, None, 1)?;
+        assert!(approx_eq(&reconstruct(&decomposition.u, &decomposition.singular_values, &decomposition.v)?, &b, LINALG_TOLERANCE));
+        assert_eq!(svd(&b, None, 0), Err(MatrixError::NoConvergence { op: "svd", iterations: 0 }));
+        Ok(())
+    }
+
+    // valores singulares bem pequenos nao perdem precisao, o que aconteceria elevando o condicionamento ao quadrado
+    #[test]
+    fn svd_ill_conditioned() -> Result<(), MatrixError> {
+        let expected = [1.0, 1e-4, 1e-9];
+        let mut a = Matrix::new(3, 3);
+        for (i, &sigma) in expected.iter().enumerate() {
+            a[(i, i)] = sigma;
+        }
+        // mistura as linhas e as colunas com rotacoes, para a matriz nao ser diagonal
+        let (c, s) = (0.6, 0.8);
+        let q = Matrix::try_from(vec![vec![c, -s, 0.0], vec![s, c, 0.0], vec![0.0, 0.0, 1.0]])?;
+        let r = Matrix::try_from(vec![vec![1.0, 0.0, 0.0], vec![0.0, c, -s], vec![0.0, s, c]])?;
+        let a = mul_matrix_sequential(&mul_matrix_sequential(&q, &a)?, &r)?;
+        let svd = svd_sequential(&a)?;
+        for (sigma, expected) in svd.singular_values.iter().zip(expected) {
+            assert!((sigma / expected - 1.0).abs() < 1e-6, "{} != {}", sigma, expected);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,564 @@
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::matrix::{Matrix, MatrixError};
+use crate::numeric::Numeric;
+use crate::sparse::CsrMatrix;
+
+// assinatura no inicio do formato binario
+const MAGIC: &[u8; 4] = b"MTXB";
+// tamanho do cabecalho binario: assinatura, tipo, 3 bytes reservados, linhas e colunas em u64
+const HEADER_LEN: usize = 4 + 4 + 8 + 8;
+
+// erros ao ler ou escrever matrizes em arquivo
+#[derive(Debug)]
+pub enum FormatError {
+    // erro do sistema de arquivos ou do leitor
+    Io(io::Error),
+    // conteudo invalido na linha line do arquivo (comecando em 1)
+    Parse { line: usize, message: String },
+    // o arquivo binario guarda elementos de outro tipo
+    DType { expected: &'static str, found: String },
+    // a extensao do arquivo nao corresponde a nenhum formato conhecido
+    UnknownFormat(String),
+    // os dados lidos nao formam uma matriz valida
+    Matrix(MatrixError),
+}
+
+impl Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FormatError::Io(err) => write!(f, "i/o error: {}", err),
+            FormatError::Parse { line, message } => write!(f, "parse error on line {}: {}", line, message),
+            FormatError::DType { expected, found } => write!(
+                f,
+                "file holds {} elements, expected {}",
+                found, expected
+            ),
+            FormatError::UnknownFormat(name) => write!(
+                f,
+                "unknown matrix format for {} (expected .csv, .tsv, .mtx or .bin)",
+                name
+            ),
+            FormatError::Matrix(err) => write!(f, "invalid matrix: {}", err),
+        }
+    }
+}
+
+impl Error for FormatError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            FormatError::Io(err) => Some(err),
+            FormatError::Matrix(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for FormatError {
+    fn from(err: io::Error) -> FormatError {
+        FormatError::Io(err)
+    }
+}
+
+impl From<MatrixError> for FormatError {
+    fn from(err: MatrixError) -> FormatError {
+        FormatError::Matrix(err)
+    }
+}
+
+// os erros do serde_json ja trazem a linha; os de leitura continuam sendo de i/o
+impl From<serde_json::Error> for FormatError {
+    fn from(err: serde_json::Error) -> FormatError {
+        if err.is_io() {
+            return FormatError::Io(err.into());
+        }
+        FormatError::Parse { line: err.line(), message: err.to_string() }
+    }
+}
+
+fn parse_error(line: usize, message: String) -> FormatError {
+    FormatError::Parse { line, message }
+}
+
+// tipos de elemento que podem ser lidos e escritos em arquivo
+// os formatos de texto usam FromStr e Display, o binario usa os bytes em little endian
+pub trait Element: Numeric + FromStr + Display {
+    // codigo do tipo no cabecalho binario
+    const DTYPE: u8;
+    // nome do tipo, usado nas mensagens de erro
+    const NAME: &'static str;
+    // campo do Matrix Market: integer ou real
+    const FIELD: &'static str;
+    // quantidade de bytes de cada elemento no formato binario
+    const SIZE: usize;
+
+    fn write_le(self, out: &mut Vec<u8>);
+
+    fn read_le(bytes: &[u8]) -> Self;
+}
+
+macro_rules! impl_element {
+    ($($t:ty => $code:expr, $field:expr);* $(;)?) => {
+        $(
+            impl Element for $t {
+                const DTYPE: u8 = $code;
+                const NAME: &'static str = stringify!($t);
+                const FIELD: &'static str = $field;
+                const SIZE: usize = std::mem::size_of::<$t>();
+
+                fn write_le(self, out: &mut Vec<u8>) {
+                    out.extend_from_slice(&self.to_le_bytes());
+                }
+
+                fn read_le(bytes: &[u8]) -> $t {
+                    <$t>::from_le_bytes(bytes.try_into().unwrap())
+                }
+            }
+        )*
+    };
+}
+
+impl_element!(
+    i8 => 1, "integer";
+    i16 => 2, "integer";
+    i32 => 3, "integer";
+    i64 => 4, "integer";
+    u8 => 5, "integer";
+    u16 => 6, "integer";
+    u32 => 7, "integer";
+    u64 => 8, "integer";
+    f32 => 9, "real";
+    f64 => 10, "real";
+);
+
+// nome do tipo guardado no cabecalho binario, para a mensagem de erro quando nao bate
+fn dtype_name(code: u8) -> String {
+    let names = ["i8", "i16", "i32", "i64", "u8", "u16", "u32", "u64", "f32", "f64"];
+    match names.get((code as usize).wrapping_sub(1)) {
+        Some(name) => name.to_string(),
+        None => format!("unknown dtype {}", code),
+    }
+}
+
+fn parse_value<T: Element>(text: &str, line: usize) -> Result<T, FormatError> {
+    text.parse()
+        .map_err(|_| parse_error(line, format!("invalid {} value {:?}", T::NAME, text)))
+}
+
+fn parse_index(text: Option<&str>, line: usize, what: &str) -> Result<usize, FormatError> {
+    let text = text.ok_or_else(|| parse_error(line, format!("missing {}", what)))?;
+    text.parse()
+        .map_err(|_| parse_error(line, format!("invalid {} {:?}", what, text)))
+}
+
+// ---------- CSV / TSV ----------
+
+// le uma matriz de texto com os elementos separados por delimiter (',' para CSV, '\t' para TSV)
+// uma linha do arquivo por linha da matriz, linhas em branco sao ignoradas
+pub fn read_delimited<T: Element>(reader: impl BufRead, delimiter: char) -> Result<Matrix<T>, FormatError> {
+    let mut rows: Vec<Vec<T>> = Vec::new();
+    for (n, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let row = line
+            .split(delimiter)
+            .map(|field| parse_value(field.trim(), n + 1))
+            .collect::<Result<Vec<T>, FormatError>>()?;
+        rows.push(row);
+    }
+    Ok(Matrix::try_from(rows)?)
+}
+
+// escreve a matriz como texto, com os elementos de cada linha separados por delimiter
+pub fn write_delimited<T: Element>(mut writer: impl Write, m: &Matrix<T>, delimiter: char) -> Result<(), FormatError> {
+    for row in m.iter_rows() {
+        for (j, x) in row.iter().enumerate() {
+            if j > 0 {
+                write!(writer, "{}", delimiter)?;
+            }
+            write!(writer, "{}", x)?;
+        }
+        writeln!(writer)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+// ---------- Matrix Market ----------
+
+// o que o cabecalho %%MatrixMarket descreve
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MarketLayout {
+    // densa, elementos em ordem de colunas
+    Array,
+    // esparsa, uma linha "i j valor" por elemento, com indices a partir de 1
+    Coordinate,
+}
+
+// conteudo de um arquivo Matrix Market, antes de virar matriz densa ou esparsa
+struct MarketData<T> {
+    rows: usize,
+    cols: usize,
+    // linha do arquivo com o tamanho, para reportar tamanhos que nao cabem na memoria
+    size_line: usize,
+    entries: Vec<(usize, usize, T)>,
+}
+
+// le o cabecalho, a linha de tamanho e os elementos de um arquivo Matrix Market
+// aceita os campos integer e real e as simetrias general e symmetric
+// na simetrica so o triangulo de baixo esta no arquivo, o de cima e espelhado aqui
+fn read_market_data<T: Element>(reader: impl BufRead) -> Result<MarketData<T>, FormatError> {
+    let mut lines = reader.lines().enumerate();
+
+    let header = match lines.next() {
+        Some((_, line)) => line?,
+        None => return Err(parse_error(1, "empty file".to_string())),
+    };
+    let words: Vec<String> = header.split_whitespace().map(|w| w.to_lowercase()).collect();
+    if words.len() != 5 || words[0] != "%%matrixmarket" || words[1] != "matrix" {
+        return Err(parse_error(1, format!("invalid header {:?}", header)));
+    }
+    let layout = match words[2].as_str() {
+        "array" => MarketLayout::Array,
+        "coordinate" => MarketLayout::Coordinate,
+        other => return Err(parse_error(1, format!("unsupported format {:?}", other))),
+    };
+    if words[3] != "integer" && words[3] != "real" {
+        return Err(parse_error(1, format!("unsupported field {:?}", words[3])));
+    }
+    let symmetric = match words[4].as_str() {
+        "general" => false,
+        "symmetric" => true,
+        other => return Err(parse_error(1, format!("unsupported symmetry {:?}", other))),
+    };
+
+    // pula os comentarios ate a linha de tamanho
+    let mut data_lines = lines.filter_map(|(n, line)| match line {
+        Ok(line) if line.trim().is_empty() || line.starts_with('%') => None,
+        other => Some((n + 1, other)),
+    });
+    let (size_line, size) = match data_lines.next() {
+        Some((n, line)) => (n, line?),
+        None => return Err(parse_error(2, "missing size line".to_string())),
+    };
+    let mut fields = size.split_whitespace();
+    let rows = parse_index(fields.next(), size_line, "row count")?;
+    let cols = parse_index(fields.next(), size_line, "column count")?;
+    // os tamanhos vem do arquivo, entao a conta pode estourar
+    if rows == usize::MAX {
+        return Err(parse_error(size_line, format!("row count {} is too large", rows)));
+    }
+    let expected = match layout {
+        MarketLayout::Array if symmetric => rows.checked_add(1).and_then(|r| r.checked_mul(rows)).map(|n| n / 2),
+        MarketLayout::Array => rows.checked_mul(cols),
+        MarketLayout::Coordinate => Some(parse_index(fields.next(), size_line, "entry count")?),
+    };
+    let expected = expected
+        .ok_or_else(|| parse_error(size_line, format!("matrix size {}x{} is too large", rows, cols)))?;
+    if symmetric && rows != cols {
+        return Err(MatrixError::NotSquare { rows, cols, op: "symmetric matrix market" }.into());
+    }
+
+    // o vetor cresce conforme os elementos sao lidos, sem reservar o tamanho pedido pelo cabecalho
+    let mut entries = Vec::new();
+    // posicao do proximo elemento no formato array, que vem em ordem de colunas
+    let (mut i, mut j) = (0, 0);
+    let mut last_line = size_line;
+    for (n, line) in data_lines {
+        let line = line?;
+        last_line = n;
+        if entries.len() == expected {
+            return Err(parse_error(n, format!("more than {} entries", expected)));
+        }
+        let mut fields = line.split_whitespace();
+        let entry = match layout {
+            MarketLayout::Array => {
+                let x = parse_value(fields.next().unwrap(), n)?;
+                let entry = (i, j, x);
+                i += 1;
+                if i == rows {
+                    j += 1;
+                    i = if symmetric { j } else { 0 };
+                }
+                entry
+            }
+            MarketLayout::Coordinate => {
+                let r = parse_index(fields.next(), n, "row index")?;
+                let c = parse_index(fields.next(), n, "column index")?;
+                if r == 0 || c == 0 || r > rows || c > cols {
+                    return Err(parse_error(n, format!("index ({}, {}) outside {}x{}", r, c, rows, cols)));
+                }
+                let text = fields.next().ok_or_else(|| parse_error(n, "missing value".to_string()))?;
+                (r - 1, c - 1, parse_value(text, n)?)
+            }
+        };
+        entries.push(entry);
+    }
+    if entries.len() != expected {
+        return Err(parse_error(
+            last_line,
+            format!("expected {} entries, found {}", expected, entries.len()),
+        ));
+    }
+
+    if symmetric {
+        let mirrored: Vec<_> = entries.iter().filter(|e| e.0 != e.1).map(|&(r, c, x)| (c, r, x)).collect();
+        entries.extend(mirrored);
+    }
+    Ok(MarketData { rows, cols, size_line, entries })
+}
+
+// le um arquivo Matrix Market (array ou coordinate) como matriz densa
+pub fn read_matrix_market<T: Element>(reader: impl BufRead) -> Result<Matrix<T>, FormatError> {
+    let data = read_market_data::<T>(reader)?;
+    // um arquivo coordinate pequeno pode pedir uma matriz densa que nao cabe em usize elementos
+    if data.rows.checked_mul(data.cols).is_none() {
+        return Err(parse_error(data.size_line, format!("matrix size {}x{} is too large", data.rows, data.cols)));
+    }
+    let mut m = Matrix::new(data.rows, data.cols);
+    for (i, j, x) in data.entries {
+        m[(i, j)] += x;
+    }
+    Ok(m)
+}
+
+// le um arquivo Matrix Market (array ou coordinate) como matriz esparsa
+pub fn read_matrix_market_sparse<T: Element>(reader: impl BufRead) -> Result<CsrMatrix<T>, FormatError> {
+    let data = read_market_data::<T>(reader)?;
+    Ok(CsrMatrix::from_triplets(data.rows, data.cols, data.entries)?)
+}
+
+// escreve a matriz densa no formato array do Matrix Market, em ordem de colunas
+pub fn write_matrix_market<T: Element>(mut writer: impl Write, m: &Matrix<T>) -> Result<(), FormatError> {
+    writeln!(writer, "%%MatrixMarket matrix array {} general", T::FIELD)?;
+    writeln!(writer, "{} {}", m.rows(), m.cols())?;
+    for j in 0..m.cols() {
+        for i in 0..m.rows() {
+            writeln!(writer, "{}", m[(i, j)])?;
+        }
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+// escreve a matriz esparsa no formato coordinate do Matrix Market, so com os elementos guardados
+pub fn write_matrix_market_sparse<T: Element>(mut writer: impl Write, m: &CsrMatrix<T>) -> Result<(), FormatError> {
+    writeln!(writer, "%%MatrixMarket matrix coordinate {} general", T::FIELD)?;
+    writeln!(writer, "{} {} {}", m.rows(), m.cols(), m.nnz())?;
+    for (i, j, x) in m.triplets() {
+        writeln!(writer, "{} {} {}", i + 1, j + 1, x)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+// ---------- binario ----------
+
+// formato binario compacto: "MTXB", o codigo do tipo (Element::DTYPE), 3 bytes reservados,
+// linhas e colunas em u64 little endian, e depois os elementos em ordem de linhas, em little endian
+pub fn write_binary<T: Element>(mut writer: impl Write, m: &Matrix<T>) -> Result<(), FormatError> {
+    let mut bytes = Vec::with_capacity(HEADER_LEN + m.as_slice().len() * T::SIZE);
+    bytes.extend_from_slice(MAGIC);
+    bytes.extend_from_slice(&[T::DTYPE, 0, 0, 0]);
+    bytes.extend_from_slice(&(m.rows() as u64).to_le_bytes());
+    bytes.extend_from_slice(&(m.cols() as u64).to_le_bytes());
+    for &x in m.as_slice() {
+        x.write_le(&mut bytes);
+    }
+    writer.write_all(&bytes)?;
+    writer.flush()?;
+    Ok(())
+}
+
+// le o formato binario, conferindo a assinatura, o tipo dos elementos e o tamanho dos dados
+pub fn read_binary<T: Element>(mut reader: impl Read) -> Result<Matrix<T>, FormatError> {
+    let mut header = [0u8; HEADER_LEN];
+    reader.read_exact(&mut header)?;
+    if &header[0..4] != MAGIC {
+        return Err(parse_error(1, "missing binary matrix signature".to_string()));
+    }
+    if header[4] != T::DTYPE {
+        return Err(FormatError::DType { expected: T::NAME, found: dtype_name(header[4]) });
+    }
+    let rows = u64::from_le_bytes(header[8..16].try_into().unwrap()) as usize;
+    let cols = u64::from_le_bytes(header[16..24].try_into().unwrap()) as usize;
+
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes)?;
+    let found = bytes.len() / T::SIZE;
+    if bytes.len() % T::SIZE != 0 || Some(found) != rows.checked_mul(cols) {
+        return Err(MatrixError::Length { expected: rows.saturating_mul(cols), found }.into());
+    }
+    let data = bytes.chunks_exact(T::SIZE).map(T::read_le).collect();
+    Ok(Matrix::from_vec(rows, cols, data)?)
+}
+
+// ---------- arquivos ----------
+
+// formatos de arquivo reconhecidos pela extensao
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Csv,
+    Tsv,
+    MatrixMarket,
+    Binary,
+}
+
+impl Format {
+    // escolhe o formato pela extensao: .csv, .tsv, .mtx ou .bin
+    pub fn from_path(path: &Path) -> Result<Format, FormatError> {
+        let ext = path.extension().and_then(|e| e.to_str()).map(|e| e.to_lowercase());
+        match ext.as_deref() {
+            Some("csv") => Ok(Format::Csv),
+            Some("tsv") => Ok(Format::Tsv),
+            Some("mtx") => Ok(Format::MatrixMarket),
+            Some("bin") => Ok(Format::Binary),
+            _ => Err(FormatError::UnknownFormat(path.display().to_string())),
+        }
+    }
+}
+
+// le uma matriz densa de um arquivo, no formato indicado pela extensao
+pub fn read_matrix_file<T: Element>(path: impl AsRef<Path>) -> Result<Matrix<T>, FormatError> {
+    let path = path.as_ref();
+    let format = Format::from_path(path)?;
+    let reader = BufReader::new(File::open(path)?);
+    match format {
+        Format::Csv => read_delimited(reader, ','),
+        Format::Tsv => read_delimited(reader, '\t'),
+        Format::MatrixMarket => read_matrix_market(reader),
+        Format::Binary => read_binary(reader),
+    }
+}
+
+// grava uma matriz densa em um arquivo, no formato indicado pela extensao
+pub fn write_matrix_file<T: Element>(path: impl AsRef<Path>, m: &Matrix<T>) -> Result<(), FormatError> {
+    let path = path.as_ref();
+    let format = Format::from_path(path)?;
+    let writer = BufWriter::new(File::create(path)?);
+    match format {
+        Format::Csv => write_delimited(writer, m, ','),
+        Format::Tsv => write_delimited(writer, m, '\t'),
+        Format::MatrixMarket => write_matrix_market(writer, m),
+        Format::Binary => write_binary(writer, m),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    use crate::random::{create_matrix_from_random, create_sparse_matrix_from_random};
+
+    // CSV e TSV em memoria
+    #[test]
+    fn delimited_round_trip() -> Result<(), FormatError> {
+        let m = create_matrix_from_random(30, 20, 100).map(|&v| v - 50);
+        for delimiter in [',', '\t'] {
+            let mut buffer = Vec::new();
+            write_delimited(&mut buffer, &m, delimiter)?;
+            assert_eq!(read_delimited::<i32>(Cursor::new(buffer), delimiter)?, m);
+        }
+        // os floats sao escritos com digitos suficientes para voltar exatamente ao mesmo valor
+        let f = m.map(|&v| v as f64 / 7.0);
+        let mut buffer = Vec::new();
+        write_delimited(&mut buffer, &f, ',')?;
+        assert_eq!(read_delimited::<f64>(Cursor::new(buffer), ',')?, f);
+        Ok(())
+    }
+
+    // Matrix Market denso e esparso
+    #[test]
+    fn matrix_market_round_trip() -> Result<(), FormatError> {
+        let f = create_matrix_from_random(30, 20, 100).map(|&v| v as f64 / 7.0);
+        let mut buffer = Vec::new();
+        write_matrix_market(&mut buffer, &f)?;
+        assert_eq!(read_matrix_market::<f64>(Cursor::new(buffer))?, f);
+
+        let sparse = create_sparse_matrix_from_random(40, 25, 0.1, 10);
+        let mut buffer = Vec::new();
+        write_matrix_market_sparse(&mut buffer, &sparse)?;
+        assert_eq!(read_matrix_market_sparse::<i32>(Cursor::new(buffer.clone()))?, sparse);
+        assert_eq!(read_matrix_market::<i32>(Cursor::new(buffer))?, sparse.to_dense());
+        Ok(())
+    }
+
+    // simetrico: so o triangulo de baixo esta no arquivo
+    #[test]
+    fn matrix_market_symmetric() -> Result<(), FormatError> {
+        let expected = Matrix::try_from(vec![vec![4, 0, 2], vec![0, 0, -1], vec![2, -1, 0]])?;
+        let text = "%%MatrixMarket matrix coordinate integer symmetric\n% comentario\n3 3 3\n1 1 4\n3 1 2\n3 2 -1\n";
+        assert_eq!(read_matrix_market::<i32>(Cursor::new(text))?, expected);
+        let text = "%%MatrixMarket matrix array integer symmetric\n3 3\n4\n0\n2\n0\n-1\n0\n";
+        assert_eq!(read_matrix_market::<i32>(Cursor::new(text))?, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn binary_round_trip() -> Result<(), FormatError> {
+        let f = create_matrix_from_random(30, 20, 100).map(|&v| v as f64 / 7.0);
+        let mut buffer = Vec::new();
+        write_binary(&mut buffer, &f)?;
+        assert_eq!(buffer.len(), 24 + 30 * 20 * 8);
+        assert_eq!(read_binary::<f64>(Cursor::new(buffer.clone()))?, f);
+        assert!(matches!(read_binary::<i32>(Cursor::new(buffer.clone())), Err(FormatError::DType { .. })));
+        buffer.pop();
+        assert!(matches!(read_binary::<f64>(Cursor::new(buffer)), Err(FormatError::Matrix(MatrixError::Length { .. }))));
+        Ok(())
+    }
+
+    // entradas invalidas viram erros com a linha do problema
+    #[test]
+    fn invalid_text_inputs() {
+        assert!(matches!(read_delimited::<i32>(Cursor::new("1,2\n3,x\n"), ','), Err(FormatError::Parse { line: 2, .. })));
+        assert!(matches!(
+            read_delimited::<i32>(Cursor::new("1,2\n3\n"), ','),
+            Err(FormatError::Matrix(MatrixError::Ragged { row: 1, .. }))
+        ));
+        assert!(matches!(read_matrix_market::<i32>(Cursor::new("1 2\n")), Err(FormatError::Parse { line: 1, .. })));
+        let text = "%%MatrixMarket matrix coordinate integer general\n2 2 1\n3 1 5\n";
+        assert!(matches!(read_matrix_market::<i32>(Cursor::new(text)), Err(FormatError::Parse { line: 3, .. })));
+    }
+
+    // tamanhos do cabecalho que estouram usize viram erro em vez de panic
+    #[test]
+    fn matrix_market_huge_sizes() {
+        let huge = usize::MAX;
+        let text = format!("%%MatrixMarket matrix array integer general\n{} {}\n1\n", huge, 2);
+        assert!(matches!(read_matrix_market::<i32>(Cursor::new(text)), Err(FormatError::Parse { line: 2, .. })));
+        let text = format!("%%MatrixMarket matrix array integer symmetric\n{} {}\n1\n", huge / 2, huge / 2);
+        assert!(matches!(read_matrix_market::<i32>(Cursor::new(text)), Err(FormatError::Parse { line: 2, .. })));
+        let text = format!("%%MatrixMarket matrix coordinate integer general\n{} {} 1\n1 1 5\n", huge, huge);
+        assert!(matches!(read_matrix_market_sparse::<i32>(Cursor::new(text.clone())), Err(FormatError::Parse { line: 2, .. })));
+        // a contagem de elementos nao reserva memoria: o arquivo termina antes e o erro aponta a ultima linha
+        let text = format!("%%MatrixMarket matrix coordinate integer general\n{} {} {}\n1 1 5\n", huge / 2, huge / 2, huge);
+        assert!(matches!(read_matrix_market_sparse::<i32>(Cursor::new(text.clone())), Err(FormatError::Parse { line: 3, .. })));
+        assert!(matches!(read_matrix_market::<i32>(Cursor::new(text)), Err(FormatError::Parse { line: 3, .. })));
+        // esparsa grande com poucos elementos e valida, mas nao cabe como matriz densa
+        let text = format!("%%MatrixMarket matrix coordinate integer general\n{} {} 1\n1 1 5\n", huge / 2, huge / 2);
+        assert!(matches!(read_matrix_market::<i32>(Cursor::new(text)), Err(FormatError::Parse { line: 2, .. })));
+    }
+
+    // arquivos de verdade, com o formato escolhido pela extensao
+    #[test]
+    fn files_by_extension() -> Result<(), FormatError> {
+        let m = create_matrix_from_random(30, 20, 100).map(|&v| v - 50);
+        let dir = std::env::temp_dir();
+        for name in ["matriz_threads_test.csv", "matriz_threads_test.tsv", "matriz_threads_test.mtx", "matriz_threads_test.bin"] {
+            let path = dir.join(name);
+            write_matrix_file(&path, &m)?;
+            assert_eq!(read_matrix_file::<i32>(&path)?, m);
+            std::fs::remove_file(&path)?;
+        }
+        assert!(matches!(read_matrix_file::<i32>(dir.join("matriz.txt")), Err(FormatError::UnknownFormat(_))));
+        Ok(())
+    }
+}
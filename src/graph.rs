@@ -0,0 +1,222 @@
+use crate::matrix::{Matrix, MatrixError};
+use crate::ops::{check_square, split_rows_mut};
+use crate::pool;
+use crate::semiring::{mul_matrix_semiring_sequential, mul_matrix_semiring_threads, Boolean};
+
+// resultado do Floyd-Warshall: distancia minima entre cada par de vertices (infinito se nao existe caminho)
+// e, se pedido, o proximo vertice de cada caminho para poder montar o caminho inteiro
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShortestPaths {
+    pub dist: Matrix<f64>,
+    next: Option<Matrix<Option<usize>>>,
+}
+
+impl ShortestPaths {
+    // monta o caminho de from ate to, incluindo os dois
+    // retorna None se nao existe caminho ou se os caminhos nao foram pedidos
+    pub fn path(&self, from: usize, to: usize) -> Option<Vec<usize>> {
+        let next = self.next.as_ref()?;
+        next[(from, to)]?;
+        let mut path = vec![from];
+        let mut at = from;
+        while at != to {
+            at = next[(at, to)]?;
+            path.push(at);
+        }
+        Some(path)
+    }
+}
+
+// estado inicial do Floyd-Warshall: distancias iguais a adjacencia, com 0 na diagonal,
+// e o proximo vertice de cada aresta existente e o proprio destino
+fn init_paths(adjacency: &Matrix<f64>, with_paths: bool) -> (Matrix<f64>, Option<Matrix<Option<usize>>>) {
+    let n = adjacency.rows();
+    let mut dist = adjacency.clone();
+    let mut next = Matrix::filled(n, n, None);
+    for i in 0..n {
+        if dist[(i, i)] > 0.0 {
+            dist[(i, i)] = 0.0;
+        }
+        for j in 0..n {
+            if dist[(i, j)].is_finite() {
+                next[(i, j)] = Some(j);
+            }
+        }
+    }
+    (dist, if with_paths { Some(next) } else { None })
+}
+
+// atualiza as linhas de dist (e de next, se existir) passando pelo vertice k
+// row_k e uma copia da linha k, que nao muda durante a iteracao k
+fn relax_rows(dist: &mut [f64], next: Option<&mut [Option<usize>]>, n: usize, k: usize, row_k: &[f64]) {
+    let mut next_rows = next.map(|next| next.chunks_mut(n));
+    for d in dist.chunks_mut(n) {
+        let mut next_row = next_rows.as_mut().and_then(|rows| rows.next());
+        let dik = d[k];
+        if dik == f64::INFINITY {
+            continue;
+        }
+        for j in 0..n {
+            let through = dik + row_k[j];
+            if through < d[j] {
+                d[j] = through;
+                // o caminho ate j agora comeca igual ao caminho ate k
+                if let Some(row) = next_row.as_mut() {
+                    row[j] = row[k];
+                }
+            }
+        }
+    }
+}
+
+// verifica se sobrou distancia negativa na diagonal, o que indica um ciclo negativo
+fn check_negative_cycle(dist: &Matrix<f64>) -> Result<(), MatrixError> {
+    for i in 0..dist.rows() {
+        if dist[(i, i)] < 0.0 {
+            return Err(MatrixError::NegativeCycle { vertex: i });
+        }
+    }
+    Ok(())
+}
+
+// caminhos minimos entre todos os pares de vertices (Floyd-Warshall)
+// adjacency[(i, j)] e o peso da aresta i -> j, f64::INFINITY quando ela nao existe
+// com with_paths os caminhos podem ser montados depois com ShortestPaths::path
+pub fn floyd_warshall_sequential(adjacency: &Matrix<f64>, with_paths: bool) -> Result<ShortestPaths, MatrixError> {
+    check_square(adjacency, "floyd_warshall")?;
+    let n = adjacency.rows();
+    let (mut dist, mut next) = init_paths(adjacency, with_paths);
+
+    for k in 0..n {
+        let row_k = dist.row(k).to_vec();
+        relax_rows(dist.as_mut_slice(), next.as_mut().map(|m| m.as_mut_slice()), n, k, &row_k);
+    }
+    check_negative_cycle(&dist)?;
+    Ok(ShortestPaths { dist, next })
+}
+
+// Floyd-Warshall usando threads: para cada vertice k, as linhas sao divididas entre as threads
+// igual a mul_matrix_threads, pois cada linha i so depende dela mesma e da linha k
+pub fn floyd_warshall_threads(adjacency: &Matrix<f64>, with_paths: bool, thr: usize) -> Result<ShortestPaths, MatrixError> {
+    check_square(adjacency, "floyd_warshall")?;
+    let n = adjacency.rows();
+    let (mut dist, mut next) = init_paths(adjacency, with_paths);
+
+    for k in 0..n {
+        let row_k = dist.row(k).to_vec();
+        let row_k = &row_k;
+        let dist_parts = split_rows_mut(dist.as_mut_slice(), n, n, thr);
+        let mut next_parts = next.as_mut().map(|m| split_rows_mut(m.as_mut_slice(), n, n, thr).into_iter());
+        pool::global().scope(|s| {
+            for (_, d) in dist_parts {
+                let next_part = next_parts.as_mut().and_then(|parts| parts.next()).map(|(_, part)| part);
+                s.spawn(move || relax_rows(d, next_part, n, k, row_k));
+            }
+        });
+    }
+    check_negative_cycle(&dist)?;
+    Ok(ShortestPaths { dist, next })
+}
+
+// closure OR squared, elemento a elemento
+fn or_matrices(a: &Matrix<bool>, b: &Matrix<bool>) -> Matrix<bool> {
+    let data = a.as_slice().iter().zip(b.as_slice()).map(|(&x, &y)| x || y).collect();
+    Matrix::from_vec(a.rows(), a.cols(), data).unwrap()
+}
+
+// fecho transitivo: closure[(i, j)] e true se existe um caminho com pelo menos uma aresta de i ate j
+// eleva a matriz ao quadrado no semianel booleano (OR, AND) ate parar de mudar,
+// cada passo dobra o tamanho dos caminhos considerados, entao sao cerca de log2(n) multiplicacoes
+pub fn transitive_closure_sequential(adjacency: &Matrix<bool>) -> Result<Matrix<bool>, MatrixError> {
+    check_square(adjacency, "transitive_closure")?;
+    let mut closure = adjacency.clone();
+    loop {
+        let squared = mul_matrix_semiring_sequential::<Boolean>(&closure, &closure)?;
+        let next = or_matrices(&closure, &squared);
+        if next == closure {
+            return Ok(closure);
+        }
+        closure = next;
+    }
+}
+
+// fecho transitivo usando a multiplicacao booleana com threads
+pub fn transitive_closure_threads(adjacency: &Matrix<bool>, thr: usize) -> Result<Matrix<bool>, MatrixError> {
+    check_square(adjacency, "transitive_closure")?;
+    let mut closure = adjacency.clone();
+    loop {
+        let squared = mul_matrix_semiring_threads::<Boolean>(&closure, &closure, thr)?;
+        let next = or_matrices(&closure, &squared);
+        if next == closure {
+            return Ok(closure);
+        }
+        closure = next;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::create_matrix_from_random;
+    use crate::semiring::MinPlus;
+    use crate::testing::{assert_threads_match, semiring_paths};
+
+    // grafo pequeno, o mesmo dos testes dos semianeis
+    fn small_graph() -> Matrix<f64> {
+        let mut adjacency = Matrix::filled(4, 4, f64::INFINITY);
+        for &(i, j, w) in &[(0, 1, 4.0), (0, 2, 1.0), (2, 1, 2.0), (1, 3, 1.0), (2, 3, 5.0)] {
+            adjacency[(i, j)] = w;
+        }
+        adjacency
+    }
+
+    #[test]
+    fn floyd_warshall_small_graph() -> Result<(), MatrixError> {
+        let adjacency = small_graph();
+        let paths = floyd_warshall_sequential(&adjacency, true)?;
+        assert_eq!(paths.dist.row(0), &[0.0, 3.0, 1.0, 4.0]);
+        assert_eq!(paths.path(0, 3), Some(vec![0, 2, 1, 3]));
+        assert_eq!(paths.path(2, 2), Some(vec![2]));
+        assert_eq!(paths.path(3, 0), None);
+        assert_threads_match(Ok(paths), |thr| floyd_warshall_threads(&adjacency, true, thr));
+
+        // o fecho transitivo nao inclui a diagonal, pois o grafo nao tem ciclos
+        let closure = transitive_closure_sequential(&adjacency.map(|w| w.is_finite()))?;
+        assert_eq!(closure.to_rows(), vec![
+            vec![false, true, true, true],
+            vec![false, false, false, true],
+            vec![false, true, false, true],
+            vec![false, false, false, false],
+        ]);
+        Ok(())
+    }
+
+    // grafo aleatorio com 40 vertices, cerca de 20% das arestas e pesos de 1 a 9,
+    // comparando sequencial, threads e o semianel (min, +)
+    #[test]
+    fn random_graph_matches_min_plus() -> Result<(), MatrixError> {
+        let weights = create_matrix_from_random(40, 40, 50);
+        let random = weights.map(|&w| if w < 10 { (w % 9 + 1) as f64 } else { f64::INFINITY });
+        let sequential = floyd_warshall_sequential(&random, true)?;
+        assert_threads_match(Ok(sequential.clone()), |thr| floyd_warshall_threads(&random, true, thr));
+        let mut with_diagonal = random.clone();
+        for i in 0..40 {
+            with_diagonal[(i, i)] = 0.0;
+        }
+        assert_eq!(sequential.dist, semiring_paths::<MinPlus>(&with_diagonal)?);
+
+        let reachable = random.map(|w| w.is_finite());
+        assert_threads_match(transitive_closure_sequential(&reachable), |thr| transitive_closure_threads(&reachable, thr));
+        Ok(())
+    }
+
+    // um ciclo negativo e reportado como erro, e a matriz precisa ser quadrada e nao vazia
+    #[test]
+    fn negative_cycle_and_shape_errors() {
+        let mut negative = small_graph();
+        negative[(3, 0)] = -10.0;
+        assert_threads_match(Err(MatrixError::NegativeCycle { vertex: 0 }), |thr| floyd_warshall_threads(&negative, false, thr));
+        assert!(matches!(floyd_warshall_sequential(&Matrix::new(2, 3), false), Err(MatrixError::NotSquare { .. })));
+        assert_eq!(transitive_closure_threads(&Matrix::new(0, 0), 2), Err(MatrixError::Empty));
+    }
+}
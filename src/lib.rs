@@ -0,0 +1,23 @@
+// operacoes com matrizes usando threads: os tipos, as operacoes e os formatos de arquivo
+// o programa em main.rs usa esta biblioteca para os benchmarks
+pub mod bench;
+pub mod bitmatrix;
+pub mod cholesky;
+pub mod conway;
+pub mod eigen;
+pub mod formats;
+pub mod graph;
+pub mod lu;
+pub mod matrix;
+pub mod numeric;
+pub mod ops;
+pub mod pool;
+pub mod qr;
+pub mod random;
+pub mod report;
+pub mod semiring;
+pub mod sparse;
+pub mod strassen;
+pub mod sweep;
+#[cfg(test)]
+mod testing;
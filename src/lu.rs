@@ -0,0 +1,295 @@
+use crate::matrix::{Matrix, MatrixError};
+use crate::ops::{check_square, split_rows_mut};
+use crate::pool;
+
+// pivos com valor absoluto ate PIVOT_TOLERANCE vezes o maior elemento da matriz sao considerados zero
+// com ponto flutuante uma matriz singular quase nunca da um pivo exatamente zero, e sim um residuo minusculo
+pub const PIVOT_TOLERANCE: f64 = 1e-12;
+
+// decomposicao LU com pivoteamento parcial: P * A = L * U
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lu {
+    // L fica abaixo da diagonal (a diagonal de L e toda 1 e nao e guardada) e U na diagonal e acima
+    lu: Matrix<f64>,
+    // a linha i de P * A e a linha perm[i] de A
+    perm: Vec<usize>,
+    // 1 ou -1, conforme a quantidade de trocas de linha foi par ou impar
+    sign: f64,
+}
+
+impl Lu {
+    // tamanho da matriz decomposta
+    pub fn size(&self) -> usize {
+        self.lu.rows()
+    }
+
+    // matriz triangular inferior, com 1 na diagonal
+    pub fn l(&self) -> Matrix<f64> {
+        let n = self.size();
+        let mut l = Matrix::new(n, n);
+        for i in 0..n {
+            l.row_mut(i)[..i].copy_from_slice(&self.lu.row(i)[..i]);
+            l[(i, i)] = 1.0;
+        }
+        l
+    }
+
+    // matriz triangular superior
+    pub fn u(&self) -> Matrix<f64> {
+        let n = self.size();
+        let mut u = Matrix::new(n, n);
+        for i in 0..n {
+            u.row_mut(i)[i..].copy_from_slice(&self.lu.row(i)[i..]);
+        }
+        u
+    }
+
+    // permutacao das linhas: a linha i de P * A e a linha permutation()[i] de A
+    pub fn permutation(&self) -> &[usize] {
+        &self.perm
+    }
+
+    // o determinante de A e o produto da diagonal de U, com o sinal das trocas de linha
+    pub fn determinant(&self) -> f64 {
+        (0..self.size()).map(|i| self.lu[(i, i)]).product::<f64>() * self.sign
+    }
+
+    // resolve A * x = b com substituicao para frente (L) e para tras (U)
+    pub fn solve(&self, b: &[f64]) -> Result<Vec<f64>, MatrixError> {
+        let n = self.size();
+        if b.len() != n {
+            return Err(MatrixError::Length { expected: n, found: b.len() });
+        }
+        let mut x: Vec<f64> = self.perm.iter().map(|&p| b[p]).collect();
+        self.substitute(&mut x);
+        Ok(x)
+    }
+
+    // resolve A * X = B, uma coluna de B por vez
+    pub fn solve_matrix(&self, b: &Matrix<f64>) -> Result<Matrix<f64>, MatrixError> {
+        let n = self.size();
+        if b.rows() != n {
+            return Err(MatrixError::ShapeMismatch { left: (n, n), right: b.shape(), op: "solve" });
+        }
+        let mut x = Matrix::new(n, b.cols());
+        let mut column = vec![0.0; n];
+        for j in 0..b.cols() {
+            for (i, c) in column.iter_mut().enumerate() {
+                *c = b[(self.perm[i], j)];
+            }
+            self.substitute(&mut column);
+            for (i, &c) in column.iter().enumerate() {
+                x[(i, j)] = c;
+            }
+        }
+        Ok(x)
+    }
+
+    // inversa de A, resolvendo A * X = I
+    pub fn inverse(&self) -> Matrix<f64> {
+        let n = self.size();
+        let mut transposed = Matrix::new(n, n);
+        self.inverse_rows(0, transposed.as_mut_slice());
+        transposed.transpose()
+    }
+
+    // inversa de A usando threads: cada coluna da inversa e um sistema independente,
+    // entao as colunas sao divididas entre as threads (como linhas da transposta, para cada uma ter seu pedaco continuo)
+    pub fn inverse_threads(&self, thr: usize) -> Matrix<f64> {
+        let n = self.size();
+        let mut transposed = Matrix::new(n, n);
+        pool::global().scope(|s| {
+            for (start, chunk) in split_rows_mut(transposed.as_mut_slice(), n, n, thr) {
+                s.spawn(move || self.inverse_rows(start, chunk));
+            }
+        });
+        transposed.transpose()
+    }
+
+    // escreve em out as colunas start, start + 1, ... da inversa, cada uma como uma linha
+    fn inverse_rows(&self, start: usize, out: &mut [f64]) {
+        let n = self.size();
+        for (r, column) in out.chunks_mut(n).enumerate() {
+            // coluna start + r da identidade, ja permutada
+            for (i, c) in column.iter_mut().enumerate() {
+                *c = if self.perm[i] == start + r { 1.0 } else { 0.0 };
+            }
+            self.substitute(column);
+        }
+    }
+
+    // resolve L * U * x = y no lugar, com y ja permutado
+    fn substitute(&self, x: &mut [f64]) {
+        let n = self.size();
+        for i in 0..n {
+            let row = self.lu.row(i);
+            let sum: f64 = (0..i).map(|k| row[k] * x[k]).sum();
+            x[i] -= sum;
+        }
+        for i in (0..n).rev() {
+            let row = self.lu.row(i);
+            let sum: f64 = (i + 1..n).map(|k| row[k] * x[k]).sum();
+            x[i] = (x[i] - sum) / row[i];
+        }
+    }
+}
+
+// subtrai de cada linha do pedaco o multiplo da linha do pivo que zera a coluna k
+// o multiplicador fica guardado na coluna k, que vira a parte de L
+fn eliminate_rows(rows: &mut [f64], n: usize, k: usize, pivot_row: &[f64]) {
+    for row in rows.chunks_mut(n) {
+        let l = row[k] / pivot_row[k];
+        row[k] = l;
+        for j in k + 1..n {
+            row[j] -= l * pivot_row[j];
+        }
+    }
+}
+
+// eliminacao de Gauss com pivoteamento parcial, coluna por coluna
+// thr None faz a atualizacao das linhas abaixo do pivo sequencialmente, Some(thr) divide essas linhas entre as threads
+// as contas de cada elemento sao as mesmas nas duas versoes, entao o resultado e identico
+// uma coluna sem pivo nao para a decomposicao: ela e pulada e a primeira delas e retornada junto
+fn factor(a: &Matrix<f64>, thr: Option<usize>) -> (Lu, Option<usize>) {
+    let n = a.rows();
+    let mut lu = a.clone();
+    let mut perm: Vec<usize> = (0..n).collect();
+    let mut sign = 1.0;
+    let mut singular = None;
+    let scale = a.as_slice().iter().fold(0.0, |m: f64, x| m.max(x.abs()));
+
+    for k in 0..n {
+        // o maior elemento da coluna k, da diagonal para baixo, vira o pivo
+        let p = (k..n).fold(k, |best, i| if lu[(i, k)].abs() > lu[(best, k)].abs() { i } else { best });
+        if lu[(p, k)].abs() <= PIVOT_TOLERANCE * scale {
+            singular = singular.or(Some(k));
+            continue;
+        }
+        if p != k {
+            let (top, bottom) = lu.as_mut_slice().split_at_mut(p * n);
+            top[k * n..(k + 1) * n].swap_with_slice(&mut bottom[..n]);
+            perm.swap(p, k);
+            sign = -sign;
+        }
+
+        // as linhas abaixo do pivo sao atualizadas, e a linha do pivo so e lida
+        let (top, trailing) = lu.as_mut_slice().split_at_mut((k + 1) * n);
+        let pivot_row = &top[k * n..];
+        match thr {
+            None => eliminate_rows(trailing, n, k, pivot_row),
+            Some(thr) => pool::global().scope(|s| {
+                for (_, chunk) in split_rows_mut(trailing, n - k - 1, n, thr) {
+                    s.spawn(move || eliminate_rows(chunk, n, k, pivot_row));
+                }
+            }),
+        }
+    }
+    (Lu { lu, perm, sign }, singular)
+}
+
+// decomposicao LU sequencial, com erro Singular se algum pivo for zero
+pub fn lu_sequential(a: &Matrix<f64>) -> Result<Lu, MatrixError> {
+    check_square(a, "lu")?;
+    match factor(a, None) {
+        (_, Some(pivot)) => Err(MatrixError::Singular { pivot }),
+        (lu, None) => Ok(lu),
+    }
+}
+
+// decomposicao LU usando threads: para cada coluna, as linhas abaixo do pivo sao divididas entre as threads
+// igual ao Floyd-Warshall, pois cada linha so depende dela mesma e da linha do pivo
+pub fn lu_threads(a: &Matrix<f64>, thr: usize) -> Result<Lu, MatrixError> {
+    check_square(a, "lu")?;
+    match factor(a, Some(thr)) {
+        (_, Some(pivot)) => Err(MatrixError::Singular { pivot }),
+        (lu, None) => Ok(lu),
+    }
+}
+
+// determinante de a, zero se a for singular
+pub fn determinant(a: &Matrix<f64>, thr: usize) -> Result<f64, MatrixError> {
+    check_square(a, "determinant")?;
+    match factor(a, Some(thr)) {
+        (_, Some(_)) => Ok(0.0),
+        (lu, None) => Ok(lu.determinant()),
+    }
+}
+
+// resolve a * x = b
+pub fn solve(a: &Matrix<f64>, b: &[f64], thr: usize) -> Result<Vec<f64>, MatrixError> {
+    lu_threads(a, thr)?.solve(b)
+}
+
+// inversa de a, com a decomposicao e as colunas da inversa calculadas com threads
+pub fn inverse(a: &Matrix<f64>, thr: usize) -> Result<Matrix<f64>, MatrixError> {
+    Ok(lu_threads(a, thr)?.inverse_threads(thr))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::numeric::approx_eq;
+    use crate::ops::mul_matrix_sequential;
+    use crate::random::create_f64_matrix_from_random;
+    use crate::semiring::{identity, Standard};
+    use crate::testing::{assert_threads_match, LINALG_TOLERANCE};
+
+    // P * A = L * U, e o sistema, a inversa e o determinante montados sobre a decomposicao
+    #[test]
+    fn factors_solve_and_inverse() -> Result<(), MatrixError> {
+        let n = 40;
+        let a = create_f64_matrix_from_random(n, n, -10, 10);
+        let lu = lu_sequential(&a)?;
+        assert_threads_match(Ok(lu.clone()), |thr| lu_threads(&a, thr));
+
+        let pa = Matrix::try_from(lu.permutation().iter().map(|&p| a.row(p).to_vec()).collect::<Vec<_>>())?;
+        assert!(approx_eq(&pa, &mul_matrix_sequential(&lu.l(), &lu.u())?, LINALG_TOLERANCE));
+
+        // a * x = b com x conhecido
+        let expected = create_f64_matrix_from_random(n, 1, -10, 10);
+        let b = mul_matrix_sequential(&a, &expected)?;
+        let x = Matrix::from_vec(n, 1, solve(&a, b.as_slice(), 3)?)?;
+        assert!(approx_eq(&x, &expected, LINALG_TOLERANCE));
+        assert_eq!(lu.solve_matrix(&b)?.into_vec(), lu.solve(b.as_slice())?);
+
+        // a * inversa = I, e det(a) * det(inversa) = 1
+        let inv = lu.inverse();
+        assert_threads_match(Ok(inv.clone()), |thr| inverse(&a, thr));
+        assert!(approx_eq(&mul_matrix_sequential(&a, &inv)?, &identity::<Standard<f64>>(n), LINALG_TOLERANCE));
+        let det = determinant(&a, 3)?;
+        assert!((det * lu_sequential(&inv)?.determinant() - 1.0).abs() < LINALG_TOLERANCE);
+
+        // determinante conhecido, com troca de linha porque o primeiro elemento e zero
+        let small = Matrix::try_from(vec![vec![0.0, 2.0, 1.0], vec![1.0, 1.0, 1.0], vec![2.0, 1.0, 3.0]])?;
+        assert!((determinant(&small, 2)? + 3.0).abs() < LINALG_TOLERANCE);
+        Ok(())
+    }
+
+    // a linha 5 e a soma das linhas 1 e 2, entao a matriz e singular e nada pode virar NaN
+    #[test]
+    fn singular_matrices() -> Result<(), MatrixError> {
+        let n = 40;
+        let a = create_f64_matrix_from_random(n, n, -10, 10);
+        let mut singular = a.clone();
+        let sum: Vec<f64> = a.row(1).iter().zip(a.row(2)).map(|(x, y)| x + y).collect();
+        singular.row_mut(5).copy_from_slice(&sum);
+        let sequential = lu_sequential(&singular);
+        assert!(matches!(sequential, Err(MatrixError::Singular { .. })));
+        assert_threads_match(sequential, |thr| lu_threads(&singular, thr));
+        assert!(matches!(solve(&singular, &vec![1.0; n], 3), Err(MatrixError::Singular { .. })));
+        assert!(matches!(inverse(&singular, 3), Err(MatrixError::Singular { .. })));
+        assert_eq!(determinant(&singular, 3)?, 0.0);
+
+        let rank_one = Matrix::try_from(vec![vec![1.0, 2.0, 3.0], vec![2.0, 4.0, 6.0], vec![1.0, 0.0, 1.0]])?;
+        assert_eq!(lu_sequential(&rank_one), Err(MatrixError::Singular { pivot: 2 }));
+        Ok(())
+    }
+
+    #[test]
+    fn shape_errors() -> Result<(), MatrixError> {
+        assert!(matches!(lu_sequential(&Matrix::new(3, 4)), Err(MatrixError::NotSquare { .. })));
+        let lu = lu_sequential(&Matrix::try_from(vec![vec![2.0, 1.0], vec![1.0, 3.0]])?)?;
+        assert_eq!(lu.solve(&[1.0; 3]), Err(MatrixError::Length { expected: 2, found: 3 }));
+        Ok(())
+    }
+}
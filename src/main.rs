@@ -1,284 +1,536 @@
-use std::thread;
-use rand::Rng;
+use std::error::Error;
+use std::path::Path;
+use std::process;
 use std::time;
 
-mod conway;
-use conway::*;
+mod cli;
+
+use aoc3::bench::BenchConfig;
+use aoc3::bitmatrix::*;
+use aoc3::cholesky::*;
+use aoc3::eigen::*;
+use aoc3::formats::*;
+use aoc3::lu::*;
+use aoc3::matrix::{Matrix, MatrixError};
+use aoc3::numeric::{approx_eq, max_abs_diff, Complex, Numeric};
+use aoc3::ops::*;
+use aoc3::qr::*;
+use aoc3::random::{
+    create_bool_matrix_from_random, create_f64_matrix_from_random, create_matrix_from_random, create_matrix_in_range,
+    create_sparse_matrix_from_random, create_spd_matrix_from_random,
+};
+use aoc3::report::{Host, Record, Recorder, RegressionError, Report};
+use aoc3::semiring::*;
+use aoc3::sparse::*;
+use aoc3::strassen::*;
+use aoc3::sweep::{Sweep, SweepPoint};
+use aoc3::{bench, conway, pool, random, report};
+use cli::{Command, Options};
+
+
+#[allow(dead_code)]
+fn print_matrix(matrix: &Matrix<i32>, th: usize) {
+    println!("Matrix [{}, {}]:", matrix.rows(), matrix.cols());
+    for row in matrix.iter_rows() {
+        for col in row {
+            print!("{: >th$}", col);
+        }
+        println!();
+    }
+}
 
+// compara o produto booleano em Matrix<bool> com a BitMatrix
+fn bench_bitmatrix(config: BenchConfig, recorder: &mut Recorder) -> Result<(), MatrixError> {
+    let a = create_bool_matrix_from_random(300, 300);
+    let b = create_bool_matrix_from_random(300, 300);
+    let (bits_a, bits_b) = (BitMatrix::from_bools(&a), BitMatrix::from_bools(&b));
 
-// cria uma matrix com numeros aleatorios, de tamanho maxi de altura e maxj de largura
-// valor pode variar de 0 ate threshold
-fn create_matrix_from_random(maxi: i32, maxj: i32, threshold: i32) -> Vec<Vec<i32>> {
-    let mut rng = rand::thread_rng();
+    let (stats_s, result_s) = bench::measure(config, || mul_matrix_semiring_threads::<Boolean>(&a, &b, 10));
+    let result_s = result_s?;
+    println!("{}", recorder.add(Record::new("Bool Mul", "Semiring Threads", a.shape(), Some(10), stats_s), None));
 
-    let mut matrix = Vec::new();
-    for _ in 0..maxi {
-        let mut row = Vec::new();
-        for _ in 0..maxj {
-            // row.push(rand::random::<i32>() % threshold);
-            let n: i32 = rng.gen_range(0, threshold);
-            row.push(n);
-        }
-        matrix.push(row);
-    }
-    matrix
+    let (stats, result_b) = bench::measure(config, || mul_bitmatrix_sequential(&bits_a, &bits_b));
+    println!("{}", recorder.add(Record::new("Bool Mul", "BitMatrix Sequential", a.shape(), None, stats), Some(&stats_s)));
+
+    assert_eq!(result_s, result_b?.to_bools());
+
+    let (stats, result_b) = bench::measure(config, || mul_bitmatrix_threads(&bits_a, &bits_b, 10));
+    println!("{}", recorder.add(Record::new("Bool Mul", "BitMatrix Threads", a.shape(), Some(10), stats), Some(&stats_s)));
+
+    assert_eq!(result_s, result_b?.to_bools());
+
+    // transposta, sequencial e com threads
+    let (stats_s, result_s) = bench::measure(config, || bits_a.transpose());
+    println!("{}", recorder.add(Record::new("Bool Transpose", "BitMatrix Sequential", a.shape(), None, stats_s), None));
+
+    let (stats, result_t) = bench::measure(config, || transpose_threads(&bits_a, 10));
+    println!("{}", recorder.add(Record::new("Bool Transpose", "BitMatrix Threads", a.shape(), Some(10), stats), Some(&stats_s)));
+
+    assert_eq!(result_s, result_t);
+    Ok(())
 }
 
+// compara a multiplicacao esparsa com a densa em matrizes com 2% de elementos diferentes de zero
+fn bench_sparse(config: BenchConfig, recorder: &mut Recorder) -> Result<(), MatrixError> {
+    let a = create_sparse_matrix_from_random(300, 300, 0.02, 10);
+    let b = create_sparse_matrix_from_random(300, 300, 0.02, 10);
+    let (dense_a, dense_b) = (a.to_dense(), b.to_dense());
 
-// printa a matrix na tela
-fn print_matrix(matrix: &Vec<Vec<i32>>, th: usize) {
-    println!("Matrix [{}, {}]:", matrix.len(), matrix[0].len());
-    for row in matrix {
-        for col in row {
-            print!("{: >th$}", col);
-        }
-        println!("");
-    }
+    let (stats_d, result_d) = bench::measure(config, || mul_matrix_threads(&dense_a, &dense_b, 10));
+    let result_d = result_d?;
+    println!("{}", recorder.add(Record::new("Sparse Mul", "Dense Threads", a.shape(), Some(10), stats_d), None));
+
+    let (stats, result_s) = bench::measure(config, || spgemm_sequential(&a, &b));
+    let result_s = result_s?;
+    let record = Record::new("Sparse Mul", "SpGEMM Sequential", a.shape(), None, stats);
+    println!("{} ({} non-zeros)", recorder.add(record, Some(&stats_d)), result_s.nnz());
+
+    assert_eq!(result_d, result_s.to_dense());
+
+    let (stats, result_t) = bench::measure(config, || spgemm_threads(&a, &b, 10));
+    println!("{}", recorder.add(Record::new("Sparse Mul", "SpGEMM Threads", a.shape(), Some(10), stats), Some(&stats_d)));
+
+    assert_eq!(result_s, result_t?);
+    Ok(())
 }
 
-// faz a soma entre 2 matrizes de item i, j da m1 e item i, j da m2
-// recebe m1 e m2 como emprestimo, pois nao precisa modificar as matrizes originais
-fn sum_matrix_sequential(m1: &Vec<Vec<i32>>, m2: &Vec<Vec<i32>>) -> Vec<Vec<i32>> {
-    // matriz que sera retornada no final
-    let mut result = Vec::new();
-    // percorre as linhas da matriz
-    for i in 0..m1.len() {
-        // cria uma nova linha
-        let mut row = Vec::new();
-        for j in 0..m1[0].len() {
-            // soma os valores das matrizes
-            row.push(m1[i][j] + m2[i][j]);
+// tolerancia relativa usada para comparar resultados de ponto flutuante com a versao sequencial
+const F32_TOLERANCE: f64 = 1e-4;
+const F64_TOLERANCE: f64 = 1e-9;
+
+// operacao sequencial e a mesma operacao com threads, para o tipo de elemento T
+type SequentialOp<T> = fn(&Matrix<T>, &Matrix<T>) -> Result<Matrix<T>, MatrixError>;
+type ThreadsOp<T> = fn(&Matrix<T>, &Matrix<T>, usize) -> Result<Matrix<T>, MatrixError>;
+
+// roda soma, subtracao, multiplicacao e Strassen com elementos do tipo T
+// os resultados com threads sao comparados com o sequencial usando a tolerancia tol,
+// pois com ponto flutuante a ordem das contas muda os ultimos bits
+fn bench_type<T: Numeric>(name: &str, m1: &Matrix<T>, m2: &Matrix<T>, tol: f64, config: BenchConfig, recorder: &mut Recorder) -> Result<(), MatrixError> {
+    let ops: [(&str, SequentialOp<T>, ThreadsOp<T>); 3] = [
+        ("Sum", sum_matrix_sequential, sum_matrix_threads),
+        ("Sub", sub_matrix_sequential, sub_matrix_threads),
+        ("Mul", mul_matrix_sequential, mul_matrix_threads),
+    ];
+    for (op, sequential, threads) in ops {
+        let (stats_s, result_s) = bench::measure(config, || sequential(m1, m2));
+        let result_s = result_s?;
+        let operation = format!("{} {}", name, op);
+        println!("{}", recorder.add(Record::new(&operation, "Sequential", m1.shape(), None, stats_s), None));
+
+        let (stats, result_t) = bench::measure(config, || threads(m1, m2, 10));
+        let result_t = result_t?;
+        let record = Record::new(&operation, "Threads", m1.shape(), Some(10), stats);
+        println!("{} (max diff {:e})", recorder.add(record, Some(&stats_s)), max_abs_diff(&result_s, &result_t));
+
+        assert!(approx_eq(&result_s, &result_t, tol));
+
+        if op == "Mul" {
+            // Strassen soma os produtos em outra ordem, entao so bate exatamente com inteiros
+            let (stats, result_st) = bench::measure(config, || mul_matrix_strassen(m1, m2, DEFAULT_CUTOFF, DEFAULT_DEPTH, 10));
+            let result_st = result_st?;
+            let record = Record::new(&operation, "Strassen Threads", m1.shape(), Some(strassen_threads(DEFAULT_DEPTH, 10)), stats);
+            println!("{} (max diff {:e})", recorder.add(record, Some(&stats_s)), max_abs_diff(&result_s, &result_st));
+
+            assert!(approx_eq(&result_s, &result_st, tol));
         }
-        // adiciona a linha na matriz resultante
-        result.push(row);
     }
-    result
+    Ok(())
 }
 
+// roda bench_type para cada tipo de elemento suportado
+fn bench_types(config: BenchConfig, recorder: &mut Recorder) -> Result<(), MatrixError> {
+    let a = create_matrix_from_random(100, 100, 1000);
+    let b = create_matrix_from_random(100, 100, 1000);
+
+    // valores com parte fracionaria, de 0.00 ate 9.99
+    bench_type("f32", &a.map(|&v| v as f32 / 100.0), &b.map(|&v| v as f32 / 100.0), F32_TOLERANCE, config, recorder)?;
+    bench_type("f64", &a.map(|&v| v as f64 / 100.0), &b.map(|&v| v as f64 / 100.0), F64_TOLERANCE, config, recorder)?;
+    bench_type("i64", &a.map(|&v| i64::from_i32(v)), &b.map(|&v| i64::from_i32(v)), 0.0, config, recorder)?;
+
+    // u8 com valores 0 ou 1, senao a multiplicacao passaria de 255; a subtracao e de m1 - m1 para nao ficar negativa
+    let bits = create_matrix_from_random(100, 100, 2).map(|&v| u8::from_i32(v));
+    bench_type("u8", &bits, &bits, 0.0, config, recorder)?;
+
+    // complexos com a parte real vinda de a e a imaginaria de b
+    let data = a.as_slice().iter().zip(b.as_slice()).map(|(&re, &im)| Complex::new(re as f64 / 100.0, im as f64 / 100.0)).collect();
+    let c = Matrix::from_vec(a.rows(), a.cols(), data)?;
+    bench_type("Complex", &c, &c.map(|z| Complex::new(z.im, -z.re)), F64_TOLERANCE, config, recorder)?;
+    Ok(())
+}
 
+// soma sequencial, com threads para cada quantidade pedida e com escalonamento dinamico
+// todas as versoes precisam dar o mesmo resultado, que e retornado
+fn bench_sum(matrix1: &Matrix<i32>, matrix2: &Matrix<i32>, threads: &[usize], config: BenchConfig, recorder: &mut Recorder) -> Result<Matrix<i32>, MatrixError> {
+    // cada versao roda config.warmup vezes sem medir e config.repetitions vezes medindo
+    let (stats_s, result_s) = bench::measure(config, || sum_matrix_sequential(matrix1, matrix2));
+    let result_s = result_s?;
+    println!("{}", recorder.add(Record::new("Sum", "Sequential", matrix1.shape(), None, stats_s), None));
 
-// faz a soma entre 2 matrizes de item i, j da m1 e item i, j da m2 usando threads
-fn sum_matrix_threads(m1: &Vec<Vec<i32>>, m2: &Vec<Vec<i32>>, thr: usize) -> Vec<Vec<i32>> {
-    let mut result = Vec::new();
-    // rows determina quantas linhas temos no total
-    let rows = m1.len();
-    // rows_per_thread determina quantas linhas cada thread vai processar
-    let rows_per_thread = rows / thr;
-    // threads armazena as threads que serao criadas
-    let mut handles = vec![];
-
-    for i in 0..thr{
-        // clona as 2 matrizes para serem utilizadas na thread
-        let m1 = m1.clone();
-        let m2 = m2.clone();
-        // cria uma nova thread
-        let handle = thread::spawn(move || {
-            let mut result = Vec::new();
-            // determina onde cada thread vai comecar e terminar
-            let start = i * rows_per_thread;
-            let mut end = start + rows_per_thread;
-
-            // no caso de divisao nao exata, a ultima thread vai processar o resto das linhas
-            if i == thr - 1 {
-                end = rows;
-            }
-            // faz todo o processo, igual na funcao sequencial
-            for i in start..end {
-                let mut row = Vec::new();
-                for j in 0..m1[0].len() {
-                    row.push(m1[i][j] + m2[i][j]);
-                }
-                result.push(row);
-            }
-            // cada thread retorna o resultado dela
-            result
-        });
-        // adiciona a thread na lista de threads
-        handles.push(handle);
+    for &thr in threads {
+        // o terceiro parametro é o numero de threads
+        let (stats, result_t) = bench::measure(config, || sum_matrix_threads(matrix1, matrix2, thr));
+        println!("{}", recorder.add(Record::new("Sum", "Threads", matrix1.shape(), Some(thr), stats), Some(&stats_s)));
+
+        // garante que o resultado das 2 funcoes sao o mesmo
+        assert_eq!(result_s, result_t?);
+
+        // escalonamento dinamico, as threads pegam DEFAULT_CHUNK linhas por vez
+        let (stats, result_d) = bench::measure(config, || sum_matrix_dynamic(matrix1, matrix2, thr, DEFAULT_CHUNK));
+        println!("{}", recorder.add(Record::new("Sum", "Dynamic", matrix1.shape(), Some(thr), stats), Some(&stats_s)));
+
+        assert_eq!(result_s, result_d?);
     }
-    // percorre as threads e espera elas terminarem
-    for handle in handles {
-        let mut res = handle.join().unwrap();
-        // adiciona o resultado da thread na matriz resultante
-        result.append(&mut res);
+    Ok(result_s)
+}
+
+// o mesmo que bench_sum, para a subtracao
+fn bench_sub(matrix1: &Matrix<i32>, matrix2: &Matrix<i32>, threads: &[usize], config: BenchConfig, recorder: &mut Recorder) -> Result<Matrix<i32>, MatrixError> {
+    let (stats_s, result_s) = bench::measure(config, || sub_matrix_sequential(matrix1, matrix2));
+    let result_s = result_s?;
+    println!("{}", recorder.add(Record::new("Sub", "Sequential", matrix1.shape(), None, stats_s), None));
+
+    for &thr in threads {
+        let (stats, result_t) = bench::measure(config, || sub_matrix_threads(matrix1, matrix2, thr));
+        println!("{}", recorder.add(Record::new("Sub", "Threads", matrix1.shape(), Some(thr), stats), Some(&stats_s)));
+
+        assert_eq!(result_s, result_t?);
+
+        let (stats, result_d) = bench::measure(config, || sub_matrix_dynamic(matrix1, matrix2, thr, DEFAULT_CHUNK));
+        println!("{}", recorder.add(Record::new("Sub", "Dynamic", matrix1.shape(), Some(thr), stats), Some(&stats_s)));
+
+        assert_eq!(result_s, result_d?);
     }
-    // retorna o resultado final
-    result
+    Ok(result_s)
 }
 
-// faz a subtraco entre 2 matrizes de item i, j da m1 e item i, j da m2 
-fn sub_matrix_sequential(m1: &Vec<Vec<i32>>, m2: &Vec<Vec<i32>>) -> Vec<Vec<i32>> {
-    // tem o mesmo funcionamento que a funcao de soma, apenas com subtracao
-    let mut result = Vec::new();
-    for i in 0..m1.len() {
-        let mut row = Vec::new();
-        for j in 0..m1[0].len() {
-            row.push(m1[i][j] - m2[i][j]);
+// multiplicacao sequencial, com threads, dinamica, com cada divisao do trabalho, em blocos e de Strassen
+fn bench_mul(matrix1: &Matrix<i32>, matrix2: &Matrix<i32>, threads: &[usize], config: BenchConfig, recorder: &mut Recorder) -> Result<Matrix<i32>, MatrixError> {
+    let (stats_s, result_s) = bench::measure(config, || mul_matrix_sequential(matrix1, matrix2));
+    let result_s = result_s?;
+    println!("{}", recorder.add(Record::new("Mul", "Sequential", matrix1.shape(), None, stats_s), None));
+
+    for &thr in threads {
+        let (stats, result_t) = bench::measure(config, || mul_matrix_threads(matrix1, matrix2, thr));
+        println!("{}", recorder.add(Record::new("Mul", "Threads", matrix1.shape(), Some(thr), stats), Some(&stats_s)));
+
+        assert_eq!(result_s, result_t?);
+
+        let (stats, result_d) = bench::measure(config, || mul_matrix_dynamic(matrix1, matrix2, thr, DEFAULT_CHUNK));
+        println!("{}", recorder.add(Record::new("Mul", "Dynamic", matrix1.shape(), Some(thr), stats), Some(&stats_s)));
+
+        assert_eq!(result_s, result_d?);
+
+        // compara a divisao por linhas com a divisao da saida em blocos 2D, com e sem dividir a dimensao k
+        for (name, partition) in [
+            ("Rows", Partition::Rows),
+            ("Tiles", Partition::Tiles { tile: 32, k_splits: 1 }),
+            ("Tiles + K", Partition::Tiles { tile: 32, k_splits: 2 }),
+        ] {
+            let (stats, result_p) = bench::measure(config, || mul_matrix_partitioned(matrix1, matrix2, thr, partition));
+            let record = Record::new("Mul", &format!("Partition {}", name), matrix1.shape(), Some(thr), stats);
+            println!("{}", recorder.add(record, Some(&stats_s)));
+
+            assert_eq!(result_s, result_p?);
         }
-        result.push(row);
     }
-    result
-}
 
-// faz a subtraco entre 2 matrizes de item i, j da m1 e item i, j da m2 usando threads
-fn sub_matrix_threads(m1: &Vec<Vec<i32>>, m2: &Vec<Vec<i32>>, thr: usize) -> Vec<Vec<i32>> {
-    // mesmo funcionamento que a funcao de soma, apenas com subtracao
-    let mut result = Vec::new();
-    
-    let rows = m1.len();
-    let iter = rows / thr;
-    let mut handles = vec![];
-
-    for i in 0..thr{
-        let m1 = m1.clone();
-        let m2 = m2.clone();
-        let handle = thread::spawn(move || {
-            let mut result = Vec::new();
-            let start = i * iter;
-            let mut end = start + iter;
-
-            // in case of uneven division
-            if i == thr - 1 {
-                end = rows;
-            }
+    // multiplicacao em blocos, comparada com o resultado da multiplicacao simples
+    let (stats, result_b) = bench::measure(config, || mul_matrix_blocked_sequential(matrix1, matrix2, DEFAULT_TILE));
+    println!("{}", recorder.add(Record::new("Mul", "Blocked Sequential", matrix1.shape(), None, stats), Some(&stats_s)));
 
-            for i in start..end {
-                let mut row = Vec::new();
-                for j in 0..m1[0].len() {
-                    row.push(m1[i][j] - m2[i][j]);
-                }
-                result.push(row);
-            }
-            result
-        });
-        handles.push(handle);
+    assert_eq!(result_s, result_b?);
+
+    for &thr in threads {
+        let (stats, result_b) = bench::measure(config, || mul_matrix_blocked_threads(matrix1, matrix2, DEFAULT_TILE, thr));
+        println!("{}", recorder.add(Record::new("Mul", "Blocked Threads", matrix1.shape(), Some(thr), stats), Some(&stats_s)));
+
+        assert_eq!(result_s, result_b?);
     }
-    for handle in handles {
-        let mut res = handle.join().unwrap();
-        result.append(&mut res);
+
+    // multiplicacao de Strassen, sem threads (profundidade 0) e com os 7 produtos divididos entre as threads
+    let (stats, result_st) = bench::measure(config, || mul_matrix_strassen(matrix1, matrix2, DEFAULT_CUTOFF, 0, 1));
+    println!("{}", recorder.add(Record::new("Mul", "Strassen Sequential", matrix1.shape(), None, stats), Some(&stats_s)));
+
+    assert_eq!(result_s, result_st?);
+
+    for &thr in threads {
+        let (stats, result_st) = bench::measure(config, || mul_matrix_strassen(matrix1, matrix2, DEFAULT_CUTOFF, DEFAULT_DEPTH, thr));
+        let record = Record::new("Mul", "Strassen Threads", matrix1.shape(), Some(strassen_threads(DEFAULT_DEPTH, thr)), stats);
+        println!("{}", recorder.add(record, Some(&stats_s)));
+
+        assert_eq!(result_s, result_st?);
     }
-    result
+    Ok(result_s)
 }
 
-// faz a multiplicacao entre 2 matrizes de soma de linhas por colunas de m1 e m2 no item i, j
-fn mul_matrix_sequential(m1: &Vec<Vec<i32>>, m2: &Vec<Vec<i32>>) -> Vec<Vec<i32>> {
-    let mut result = Vec::new();
-    for i in 0..m1.len() {
-        let mut row = Vec::new();
-        for j in 0..m2[0].len() {
-            // percorre todas as linhas e colunas, somando o valor da multiplicacao de linhas por coluna
-            let mut sum = 0;
-            for k in 0..m1[0].len() {
-                // percore todas as colunas de m1 e todas as linhas de m2
-                sum += m1[i][k] * m2[k][j];
-            }
-            row.push(sum);
-        }
-        result.push(row);
-    }
-    result
+// decomposicao LU e inversa de uma matriz 200 x 200, sequencial e com threads
+fn bench_lu(config: BenchConfig, recorder: &mut Recorder) -> Result<(), MatrixError> {
+    let a = create_f64_matrix_from_random(200, 200, -10, 10);
+
+    let (stats_s, result_s) = bench::measure(config, || lu_sequential(&a));
+    let result_s = result_s?;
+    println!("{}", recorder.add(Record::new("LU", "Sequential", a.shape(), None, stats_s), None));
+
+    let (stats, result_t) = bench::measure(config, || lu_threads(&a, 10));
+    println!("{}", recorder.add(Record::new("LU", "Threads", a.shape(), Some(10), stats), Some(&stats_s)));
+
+    assert_eq!(result_s, result_t?);
+
+    let (stats_s, inv_s) = bench::measure(config, || result_s.inverse());
+    println!("{}", recorder.add(Record::new("Inverse", "Sequential", a.shape(), None, stats_s), None));
+
+    let (stats, inv_t) = bench::measure(config, || inverse(&a, 10));
+    println!("{}", recorder.add(Record::new("Inverse", "Threads", a.shape(), Some(10), stats), Some(&stats_s)));
+
+    assert_eq!(inv_s, inv_t?);
+    Ok(())
 }
 
-// faz a multiplicacao entre 2 matrizes de soma de linhas por colunas de m1 e m2 no item i, j usando threads
-fn mul_matrix_threads(m1: &Vec<Vec<i32>>, m2: &Vec<Vec<i32>>, thr: usize) -> Vec<Vec<i32>> {
-    // tem o mesmo funcionamento que a funcao de multiplicacao, apenas com threads
-    let mut result = Vec::new();
-    
-    let rows = m1.len();
-    let iter = rows / thr;
-    let mut handles = vec![];
-
-    for i in 0..thr{
-        let m1 = m1.clone();
-        let m2 = m2.clone();
-        let handle = thread::spawn(move || {
-            let mut result = Vec::new();
-            // determina onde cada thread vai comecar e terminar
-            let start = i * iter;
-            let mut end = start + iter;
-
-            // em caso de divisao nao exata, a ultima thread vai processar o resto das linhas
-            if i == thr - 1 {
-                end = rows;
-            }
+// decomposicao QR de uma matriz 400 x 100, sequencial e com threads
+fn bench_qr(config: BenchConfig, recorder: &mut Recorder) -> Result<(), MatrixError> {
+    let a = create_f64_matrix_from_random(400, 100, -10, 10);
 
-            for i in start..end {
-                let mut row = Vec::new();
-                for j in 0..m2[0].len() {
-                    let mut sum = 0;
-                    for k in 0..m1[0].len() {
-                        sum += m1[i][k] * m2[k][j];
-                    }
-                    row.push(sum);
-                }
-                result.push(row);
-            }
-            result
-        });
-        handles.push(handle);
-    }
-    for handle in handles {
-        let mut res = handle.join().unwrap();
-        result.append(&mut res);
-    }
-    result
+    let (stats_s, result_s) = bench::measure(config, || qr_sequential(&a));
+    let result_s = result_s?;
+    println!("{}", recorder.add(Record::new("QR", "Sequential", a.shape(), None, stats_s), None));
+
+    let (stats, result_t) = bench::measure(config, || qr_threads(&a, 10));
+    println!("{}", recorder.add(Record::new("QR", "Threads", a.shape(), Some(10), stats), Some(&stats_s)));
+
+    assert_eq!(result_s, result_t?);
+    Ok(())
 }
 
-fn main() {
+// decomposicao de Cholesky de uma matriz 300 x 300, sequencial e com threads
+fn bench_cholesky(config: BenchConfig, recorder: &mut Recorder) -> Result<(), MatrixError> {
+    let a = create_spd_matrix_from_random(300, 10);
 
-    // escolhe o tamanho das matrizes, linhas, colunas e valor maximo para cada elemento
-    let matrix1 = create_matrix_from_random(100, 100, 10);
-    let matrix2 = create_matrix_from_random(100, 100, 10);
-    // print_matrix(&matrix1, 3);
-    // print_matrix(&matrix2, 3);
-
-    // pega o tempo inicial
-    let mut start = time::Instant::now();
-    let result_s = sum_matrix_sequential(&matrix1, &matrix2);
-    let mut end = time::Instant::now();
-    // pega o tempo final e calcula a diferenca
-    println!("Sum - Sequential: {} ms", end.duration_since(start).as_millis());
-
-    start = time::Instant::now();
-    // o terceiro parametro é o numero de threads
-    let result_t = sum_matrix_threads(&matrix1, &matrix2, 10);
-    end = time::Instant::now();
-    println!("Sum - Threads: {} ms", end.duration_since(start).as_millis());
-
-    // garante que o resultado das 2 funcoes sao o mesmo
-    assert_eq!(result_s, result_t);
+    let (stats_s, result_s) = bench::measure(config, || cholesky_sequential(&a, DEFAULT_BLOCK));
+    let result_s = result_s?;
+    println!("{}", recorder.add(Record::new("Cholesky", "Sequential", a.shape(), None, stats_s), None));
 
+    let (stats, result_t) = bench::measure(config, || cholesky_threads(&a, DEFAULT_BLOCK, 10));
+    println!("{}", recorder.add(Record::new("Cholesky", "Threads", a.shape(), Some(10), stats), Some(&stats_s)));
 
+    assert_eq!(result_s, result_t?);
+    Ok(())
+}
 
-    let mut start = time::Instant::now();
-    let result_s = sub_matrix_sequential(&matrix1, &matrix2);
-    let mut end = time::Instant::now();
-    println!("Sub - Sequential: {} ms", end.duration_since(start).as_millis());
+// decomposicao de Jacobi de uma matriz simetrica 100 x 100, sequencial e com threads
+fn bench_eigen(config: BenchConfig, recorder: &mut Recorder) -> Result<(), MatrixError> {
+    let b = create_f64_matrix_from_random(100, 100, -10, 10);
+    let a = sum_matrix_sequential(&b, &b.transpose())?;
 
-    start = time::Instant::now();
-    let result_t = sub_matrix_threads(&matrix1, &matrix2, 10);
-    end = time::Instant::now();
-    println!("Sub - Threads: {} ms", end.duration_since(start).as_millis());
+    let (stats_s, result_s) = bench::measure(config, || symmetric_eigen_sequential(&a));
+    let result_s = result_s?;
+    println!("{}", recorder.add(Record::new("Eigen", "Jacobi Sequential", a.shape(), None, stats_s), None));
 
-    assert_eq!(result_s, result_t);
+    let (stats, result_t) = bench::measure(config, || symmetric_eigen_threads(&a, 10));
+    println!("{}", recorder.add(Record::new("Eigen", "Jacobi Threads", a.shape(), Some(10), stats), Some(&stats_s)));
 
+    assert_eq!(result_s, result_t?);
+    Ok(())
+}
 
+// matrizes usadas nos benchmarks
+// a da direita da multiplicacao precisa ter tantas linhas quanto a da esquerda tem colunas,
+// entao com matrizes aleatorias nao quadradas ela e gerada com o tamanho transposto
+struct Operands {
+    left: Matrix<i32>,
+    right: Matrix<i32>,
+    right_mul: Matrix<i32>,
+}
 
-    let mut start = time::Instant::now();
-    let result_s = mul_matrix_sequential(&matrix1, &matrix2);
-    let mut end = time::Instant::now();
-    println!("Mul - Sequential: {} ms", end.duration_since(start).as_millis());
+// le as matrizes dos arquivos --left e --right, ou gera com numeros aleatorios
+fn load_operands(options: &Options) -> Result<Operands, FormatError> {
+    if let (Some(left), Some(right)) = (&options.left, &options.right) {
+        let right: Matrix<i32> = read_matrix_file(right)?;
+        return Ok(Operands { left: read_matrix_file(left)?, right_mul: right.clone(), right });
+    }
+    let left = create_matrix_in_range(options.rows, options.cols, options.min, options.max);
+    let right = create_matrix_in_range(options.rows, options.cols, options.min, options.max);
+    let right_mul = if options.rows == options.cols {
+        right.clone()
+    } else {
+        create_matrix_in_range(options.cols, options.rows, options.min, options.max)
+    };
+    Ok(Operands { left, right, right_mul })
+}
 
-    start = time::Instant::now();
-    let result_t = mul_matrix_threads(&matrix1, &matrix2, 10);
-    end = time::Instant::now();
-    println!("Mul - Threads: {} ms", end.duration_since(start).as_millis());
+// mede cada operacao em matrizes size x size para cada tamanho em options.sizes,
+// sequencial uma vez e com threads para cada quantidade em options.threads
+// o jogo da vida entra como uma geracao, sem janela, de um tabuleiro size x size
+fn run_sweep(options: &Options, seed: u64) -> Result<Sweep, MatrixError> {
+    random::reseed(seed);
+    let ops: [(&str, SequentialOp<i32>, ThreadsOp<i32>); 3] = [
+        ("sum", sum_matrix_sequential, sum_matrix_threads),
+        ("sub", sub_matrix_sequential, sub_matrix_threads),
+        ("mul", mul_matrix_sequential, mul_matrix_threads),
+    ];
+    let mut points = Vec::new();
+    for &size in &options.sizes {
+        let matrix1 = create_matrix_in_range(size, size, options.min, options.max);
+        let matrix2 = create_matrix_in_range(size, size, options.min, options.max);
+        for (operation, sequential, threads) in ops {
+            let (stats_s, result_s) = bench::measure(options.bench, || sequential(&matrix1, &matrix2));
+            let result_s = result_s?;
+            for &thr in &options.threads {
+                let (stats, result_t) = bench::measure(options.bench, || threads(&matrix1, &matrix2, thr));
+                assert_eq!(result_s, result_t?);
+                let threads = pool::running_threads(thr, size);
+                points.push(SweepPoint { operation, size, threads, sequential: stats_s, parallel: stats });
+            }
+        }
 
-    assert_eq!(result_s, result_t);
+        // as duas versoes recebem uma copia do tabuleiro, entao o custo da copia aparece igual nas duas
+        let mut board = conway::board_new(size as u32, size as u32);
+        random::with_rng(|rng| conway::randomize_board(&mut board, size as u32, size as u32, 0.4, rng));
+        let (stats_s, result_s) = bench::measure(options.bench, || conway::update_sequential(board.clone(), size, size));
+        for &thr in &options.threads {
+            let (stats, result_t) = bench::measure(options.bench, || conway::update_threads(board.clone(), size, size, thr));
+            assert_eq!(result_s, result_t);
+            let threads = pool::running_threads(thr, size);
+            points.push(SweepPoint { operation: "life", size, threads, sequential: stats_s, parallel: stats });
+        }
+    }
+    Ok(Sweep { seed, host: Host::current(), points })
+}
 
+// compara as medicoes com as do arquivo de referencia e falha se alguma ficou lenta demais
+// os casos que nao existem na referencia (outro tamanho, outras threads) aparecem mas nao contam,
+// assim como os casos da referencia que esta execucao nao mediu
+fn compare_with_baseline(report: &Report, path: &Path, max_regression: f64) -> Result<(), Box<dyn Error>> {
+    let baseline = report::read_baseline(path)?;
+    println!("Compare - Baseline {} (max regression {}%)", path.display(), max_regression);
+    if let Some(host) = baseline.host.as_ref().filter(|&host| host != &report.host) {
+        println!("Warning: the baseline was measured on {}, this run is on {}", host, report.host);
+    }
+    let comparison = report::compare(&baseline.records, &report.records);
+    for change in &comparison.changes {
+        match (change.baseline, change.percent()) {
+            (Some(base), Some(percent)) => println!(
+                "{}: {:.0} ns -> {:.0} ns ({:+.1}%){}",
+                change.current.label(),
+                base.stats.median,
+                change.current.stats.median,
+                percent,
+                if change.is_regression(max_regression) { " REGRESSION" } else { "" }
+            ),
+            _ => println!("{}: not in the baseline", change.current.label()),
+        }
+    }
+    for record in &comparison.missing {
+        println!("{}: in the baseline but not measured in this run", record.label());
+    }
+    let regressions = comparison.changes.iter().filter(|c| c.is_regression(max_regression)).count();
+    if regressions > 0 {
+        return Err(Box::new(RegressionError { regressions, max_regression }));
+    }
+    println!("Compare - OK, no case regressed more than {}%", max_regression);
+    Ok(())
+}
+
+fn run(options: &Options) -> Result<(), Box<dyn Error>> {
+    // cria o pool de threads uma unica vez, ele e reaproveitado por todas as operacoes e pelo jogo da vida
+    // o custo de criar as threads aparece so aqui, e nao mais no tempo de cada operacao
+    let start = time::Instant::now();
+    pool::init(options.pool_threads());
+    let end = time::Instant::now();
+    println!("Pool - Startup: {} ns ({} threads)", end.duration_since(start).as_nanos(), pool::global().size());
+
+    // todas as matrizes e o tabuleiro do jogo da vida saem dessa semente,
+    // rodar de novo com --seed e ela repete exatamente os mesmos dados
+    let seed = options.seed.unwrap_or_else(random::random_seed);
+    println!("Seed: {}", seed);
+    println!("Repetitions: {} (after {} warmup runs)", options.bench.repetitions, options.bench.warmup);
+
+    let mut recorder = Recorder::new();
+    if options.command == Command::Sweep {
+        let sweep = run_sweep(options, seed)?;
+        sweep.print_table();
+        if let Some(path) = &options.report {
+            sweep.write_file(path)?;
+            println!("Report written to {}", path.display());
+        }
+        return Ok(());
+    }
+
+    random::reseed(seed);
+    let operands = load_operands(options)?;
+    // print_matrix(&operands.left, 3);
+    // print_matrix(&operands.right, 3);
+    let all = options.command == Command::All;
+
+    let mut result = None;
+    if all || options.command == Command::Sum {
+        result = Some(bench_sum(&operands.left, &operands.right, &options.threads, options.bench, &mut recorder)?);
+    }
+    if all || options.command == Command::Sub {
+        result = Some(bench_sub(&operands.left, &operands.right, &options.threads, options.bench, &mut recorder)?);
+    }
+    if all || options.command == Command::Mul {
+        result = Some(bench_mul(&operands.left, &operands.right_mul, &options.threads, options.bench, &mut recorder)?);
+    }
 
-    // 1 instancia é para o modo sequencial, determinado pelo 3 parametro, 0 para sequencial e 1 para threads
-    conway::instantiate_game(800, 800, 0, 4);
-    conway::instantiate_game(800, 800, 1, 4);
+    // so tem --output com uma unica operacao, entao result e o resultado dela
+    if let (Some(path), Some(result)) = (&options.output, &result) {
+        write_matrix_file(path, result)?;
+        println!("Result written to {}", path.display());
+    }
 
-}
\ No newline at end of file
+    if all {
+        // as mesmas operacoes com outros tipos de elemento
+        bench_types(options.bench, &mut recorder)?;
+        bench_bitmatrix(options.bench, &mut recorder)?;
+        bench_sparse(options.bench, &mut recorder)?;
+        bench_lu(options.bench, &mut recorder)?;
+        bench_qr(options.bench, &mut recorder)?;
+        bench_cholesky(options.bench, &mut recorder)?;
+        bench_eigen(options.bench, &mut recorder)?;
+    }
+
+    if options.life {
+        // 1 instancia é para o modo sequencial, determinado pelo 3 parametro, 0 para sequencial e 1 para threads
+        let sequential = conway::instantiate_game(800, 800, 0, 4, seed);
+        let threads = conway::instantiate_game(800, 800, 1, 4, seed);
+
+        // o tabuleiro tem 800 / 4 = 200 celulas de cada lado
+        let board = (200, 200);
+        for (name, stats, update_threads) in [("Sequential", sequential, None), ("Threads", threads, Some(conway::NUMBER_OF_THREADS))] {
+            if let Some(update) = stats.update {
+                recorder.add(Record::new("Life Update", name, board, update_threads, update), None);
+            }
+            if let Some(render) = stats.render {
+                recorder.add(Record::new("Life Render", name, board, None, render), None);
+            }
+        }
+    }
+
+    let report = Report { seed, host: Host::current(), config: options.bench, records: recorder.into_records() };
+    if let Some(path) = &options.report {
+        report.write_file(path)?;
+        println!("Report written to {}", path.display());
+    }
+    if let Some(path) = &options.compare {
+        compare_with_baseline(&report, path, options.max_regression)?;
+    }
+    Ok(())
+}
+
+fn main() {
+    let options = match cli::parse(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(err) => {
+            eprintln!("error: {}\n\nRun with --help to see the options.", err);
+            process::exit(cli::EXIT_USAGE);
+        }
+    };
+    if options.command == Command::Help {
+        print!("{}", cli::USAGE);
+        return;
+    }
+    if let Err(err) = run(&options) {
+        eprintln!("error: {}", err);
+        process::exit(cli::EXIT_FAILURE);
+    }
+}
@@ -0,0 +1,245 @@
+use std::error::Error;
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+// erros possiveis nas operacoes de matriz, para que quem chama possa reportar a entrada invalida
+// em vez do programa parar com panic
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MatrixError {
+    // as dimensoes das duas matrizes nao sao compativeis para a operacao op
+    ShapeMismatch { left: (usize, usize), right: (usize, usize), op: &'static str },
+    // a matriz nao tem linhas ou nao tem colunas
+    Empty,
+    // a linha row tem tamanho diferente da primeira linha
+    Ragged { row: usize, expected: usize, found: usize },
+    // o vetor de dados nao tem rows * cols elementos
+    Length { expected: usize, found: usize },
+    // o indice (row, col) fica fora de uma matriz rows x cols
+    IndexOutOfBounds { row: usize, col: usize, rows: usize, cols: usize },
+    // o resultado do elemento (row, col) nao cabe no tipo, no modo Overflow::Checked
+    Overflow { row: usize, col: usize, op: &'static str },
+    // a operacao op precisa de uma matriz quadrada
+    NotSquare { rows: usize, cols: usize, op: &'static str },
+    // o grafo tem um ciclo de peso negativo passando pelo vertice, entao nao existe caminho minimo
+    NegativeCycle { vertex: usize },
+    // o pivo da coluna pivot ficou zero na eliminacao, entao a matriz nao tem inversa
+    Singular { pivot: usize },
+    // a operacao op precisa de pelo menos tantas linhas quanto colunas
+    Wide { rows: usize, cols: usize, op: &'static str },
+    // os elementos (row, col) e (col, row) sao diferentes, mas a operacao precisa de uma matriz simetrica
+    NotSymmetric { row: usize, col: usize },
+    // o pivo da coluna pivot ficou zero ou negativo na decomposicao de Cholesky
+    NotPositiveDefinite { pivot: usize },
+    // o metodo iterativo op nao convergiu depois de iterations iteracoes
+    NoConvergence { op: &'static str, iterations: usize },
+}
+
+impl fmt::Display for MatrixError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MatrixError::ShapeMismatch { left, right, op } => write!(
+                f,
+                "shape mismatch in {}: left is {}x{}, right is {}x{}",
+                op, left.0, left.1, right.0, right.1
+            ),
+            MatrixError::Empty => write!(f, "matrix is empty"),
+            MatrixError::Ragged { row, expected, found } => write!(
+                f,
+                "ragged rows: row {} has {} columns, expected {}",
+                row, found, expected
+            ),
+            MatrixError::Length { expected, found } => write!(
+                f,
+                "data has {} elements, expected {}",
+                found, expected
+            ),
+            MatrixError::IndexOutOfBounds { row, col, rows, cols } => write!(
+                f,
+                "index ({}, {}) is outside a {}x{} matrix",
+                row, col, rows, cols
+            ),
+            MatrixError::Overflow { row, col, op } => write!(
+                f,
+                "integer overflow in {} at ({}, {})",
+                op, row, col
+            ),
+            MatrixError::NotSquare { rows, cols, op } => write!(
+                f,
+                "{} needs a square matrix, got {}x{}",
+                op, rows, cols
+            ),
+            MatrixError::NegativeCycle { vertex } => write!(
+                f,
+                "negative cycle through vertex {}",
+                vertex
+            ),
+            MatrixError::Singular { pivot } => write!(
+                f,
+                "matrix is singular: pivot {} is zero",
+                pivot
+            ),
+            MatrixError::Wide { rows, cols, op } => write!(
+                f,
+                "{} needs at least as many rows as columns, got {}x{}",
+                op, rows, cols
+            ),
+            MatrixError::NotSymmetric { row, col } => write!(
+                f,
+                "matrix is not symmetric: ({}, {}) differs from ({}, {})",
+                row, col, col, row
+            ),
+            MatrixError::NotPositiveDefinite { pivot } => write!(
+                f,
+                "matrix is not positive definite: pivot {} is not positive",
+                pivot
+            ),
+            MatrixError::NoConvergence { op, iterations } => write!(
+                f,
+                "{} did not converge after {} iterations",
+                op, iterations
+            ),
+        }
+    }
+}
+
+impl Error for MatrixError {}
+
+// matriz com armazenamento continuo, linha por linha (row-major)
+// o elemento (i, j) fica na posicao i * cols + j do vetor data
+#[derive(Debug, Clone, PartialEq)]
+pub struct Matrix<T> {
+    rows: usize,
+    cols: usize,
+    data: Vec<T>,
+}
+
+impl<T> Matrix<T> {
+    // cria uma matriz a partir de um vetor ja em ordem de linhas
+    // o tamanho do vetor precisa ser exatamente rows * cols
+    pub fn from_vec(rows: usize, cols: usize, data: Vec<T>) -> Result<Matrix<T>, MatrixError> {
+        if data.len() != rows * cols {
+            return Err(MatrixError::Length { expected: rows * cols, found: data.len() });
+        }
+        Ok(Matrix { rows, cols, data })
+    }
+
+    // quantidade de linhas
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    // quantidade de colunas
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    // retorna (linhas, colunas)
+    pub fn shape(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    // verdadeiro se a matriz nao tem nenhum elemento
+    pub fn is_empty(&self) -> bool {
+        self.rows == 0 || self.cols == 0
+    }
+
+    // retorna a linha i como um slice, sem copiar
+    pub fn row(&self, i: usize) -> &[T] {
+        &self.data[i * self.cols..(i + 1) * self.cols]
+    }
+
+    // retorna a linha i como um slice mutavel
+    pub fn row_mut(&mut self, i: usize) -> &mut [T] {
+        &mut self.data[i * self.cols..(i + 1) * self.cols]
+    }
+
+    // percorre as linhas da matriz, cada uma como um slice
+    pub fn iter_rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.rows).map(move |i| self.row(i))
+    }
+
+    // todos os elementos em ordem de linhas
+    pub fn as_slice(&self) -> &[T] {
+        &self.data
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        &mut self.data
+    }
+
+    // consome a matriz e devolve o vetor interno
+    pub fn into_vec(self) -> Vec<T> {
+        self.data
+    }
+
+    // cria uma matriz do mesmo tamanho aplicando f em cada elemento, por exemplo para trocar o tipo
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Matrix<U> {
+        Matrix { rows: self.rows, cols: self.cols, data: self.data.iter().map(f).collect() }
+    }
+}
+
+impl<T: Clone> Matrix<T> {
+    // cria uma matriz rows x cols com todos os elementos iguais a value
+    pub fn filled(rows: usize, cols: usize, value: T) -> Matrix<T> {
+        Matrix { rows, cols, data: vec![value; rows * cols] }
+    }
+
+    // converte de volta para o formato antigo de vetor de vetores
+    pub fn to_rows(&self) -> Vec<Vec<T>> {
+        self.iter_rows().map(|row| row.to_vec()).collect()
+    }
+
+    // transposta: o elemento (i, j) vira o (j, i)
+    pub fn transpose(&self) -> Matrix<T> {
+        let data = (0..self.cols).flat_map(|j| (0..self.rows).map(move |i| self.data[i * self.cols + j].clone())).collect();
+        Matrix { rows: self.cols, cols: self.rows, data }
+    }
+}
+
+impl<T: Clone + Default> Matrix<T> {
+    // cria uma matriz rows x cols preenchida com o valor padrao do tipo (0 para numeros)
+    pub fn new(rows: usize, cols: usize) -> Matrix<T> {
+        Matrix::filled(rows, cols, T::default())
+    }
+}
+
+// acesso por matrix[(i, j)]
+impl<T> Index<(usize, usize)> for Matrix<T> {
+    type Output = T;
+
+    fn index(&self, (i, j): (usize, usize)) -> &T {
+        assert!(i < self.rows && j < self.cols, "index ({}, {}) out of bounds for {}x{}", i, j, self.rows, self.cols);
+        &self.data[i * self.cols + j]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Matrix<T> {
+    fn index_mut(&mut self, (i, j): (usize, usize)) -> &mut T {
+        assert!(i < self.rows && j < self.cols, "index ({}, {}) out of bounds for {}x{}", i, j, self.rows, self.cols);
+        &mut self.data[i * self.cols + j]
+    }
+}
+
+// converte do formato antigo Vec<Vec<T>>, todas as linhas precisam ter o mesmo tamanho
+impl<T> TryFrom<Vec<Vec<T>>> for Matrix<T> {
+    type Error = MatrixError;
+
+    fn try_from(rows: Vec<Vec<T>>) -> Result<Matrix<T>, MatrixError> {
+        let n = rows.len();
+        let cols = rows.first().map_or(0, |row| row.len());
+        let mut data = Vec::with_capacity(n * cols);
+        for (i, row) in rows.into_iter().enumerate() {
+            if row.len() != cols {
+                return Err(MatrixError::Ragged { row: i, expected: cols, found: row.len() });
+            }
+            data.extend(row);
+        }
+        Ok(Matrix { rows: n, cols, data })
+    }
+}
+
+impl<T: Clone> From<Matrix<T>> for Vec<Vec<T>> {
+    fn from(matrix: Matrix<T>) -> Vec<Vec<T>> {
+        matrix.to_rows()
+    }
+}
@@ -0,0 +1,353 @@
+use std::fmt::{self, Debug, Display};
+use std::num::Wrapping;
+use std::ops::{Add, AddAssign, Mul, Sub};
+
+use crate::matrix::Matrix;
+
+// tipos de elemento aceitos pelas operacoes de soma, subtracao e multiplicacao
+// Default precisa ser o zero do tipo, ele e usado para iniciar as matrizes e os acumuladores
+// Send + Sync permitem que as threads leiam e escrevam os elementos
+pub trait Numeric:
+    Copy
+    + Default
+    + PartialEq
+    + Debug
+    + Send
+    + Sync
+    + Add<Output = Self>
+    + AddAssign
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + 'static
+{
+    // converte um inteiro, usado para gerar matrizes aleatorias de qualquer tipo
+    fn from_i32(value: i32) -> Self;
+
+    // valor absoluto (ou modulo, para complexos) como f64, usado nas comparacoes com tolerancia
+    fn magnitude(self) -> f64;
+
+    // distancia entre dois valores como f64, sem estourar em tipos sem sinal como u8
+    fn distance(self, other: Self) -> f64;
+
+    // tipo das contas intermediarias de algoritmos como Strassen, que podem passar do limite do tipo
+    // (ou ficar negativas, com tipos sem sinal) mesmo quando o resultado final cabe
+    // para inteiros e std::num::Wrapping, exato modulo 2^bits, para os outros tipos e o proprio tipo
+    type Intermediate: Numeric;
+
+    fn to_intermediate(self) -> Self::Intermediate;
+    fn from_intermediate(value: Self::Intermediate) -> Self;
+}
+
+// implementa Numeric para os tipos primitivos, que ja tem +, - e *
+// os inteiros fazem as contas intermediarias em Wrapping, que tambem implementa Numeric,
+// fazendo +, - e * dando a volta, e assim pode ser usado por qualquer kernel generico
+macro_rules! impl_numeric {
+    (@common $t:ty) => {
+        fn from_i32(value: i32) -> $t {
+            value as $t
+        }
+
+        fn magnitude(self) -> f64 {
+            (self as f64).abs()
+        }
+
+        fn distance(self, other: $t) -> f64 {
+            (self as f64 - other as f64).abs()
+        }
+    };
+    (float: $($t:ty),*) => {
+        $(
+            impl Numeric for $t {
+                impl_numeric!(@common $t);
+
+                type Intermediate = $t;
+
+                fn to_intermediate(self) -> $t {
+                    self
+                }
+
+                fn from_intermediate(value: $t) -> $t {
+                    value
+                }
+            }
+        )*
+    };
+    (int: $($t:ty),*) => {
+        $(
+            impl Numeric for $t {
+                impl_numeric!(@common $t);
+
+                type Intermediate = Wrapping<$t>;
+
+                fn to_intermediate(self) -> Wrapping<$t> {
+                    Wrapping(self)
+                }
+
+                fn from_intermediate(value: Wrapping<$t>) -> $t {
+                    value.0
+                }
+            }
+
+            impl Numeric for Wrapping<$t> {
+                fn from_i32(value: i32) -> Wrapping<$t> {
+                    Wrapping(value as $t)
+                }
+
+                fn magnitude(self) -> f64 {
+                    self.0.magnitude()
+                }
+
+                fn distance(self, other: Wrapping<$t>) -> f64 {
+                    self.0.distance(other.0)
+                }
+
+                type Intermediate = Wrapping<$t>;
+
+                fn to_intermediate(self) -> Wrapping<$t> {
+                    self
+                }
+
+                fn from_intermediate(value: Wrapping<$t>) -> Wrapping<$t> {
+                    value
+                }
+            }
+        )*
+    };
+}
+
+impl_numeric!(int: i8, i16, i32, i64, i128, u8, u16, u32, u64, u128);
+impl_numeric!(float: f32, f64);
+
+// inteiros primitivos, com as contas que verificam, dao a volta ou saturam no limite do tipo
+// usado pelos modos de estouro da ops; Wide e o tipo com o dobro de bits, onde qualquer produto cabe
+pub trait Integer: Numeric {
+    type Wide: Numeric + From<Self>;
+
+    fn checked_add(self, other: Self) -> Option<Self>;
+    fn checked_sub(self, other: Self) -> Option<Self>;
+    fn checked_mul(self, other: Self) -> Option<Self>;
+    fn wrapping_add(self, other: Self) -> Self;
+    fn wrapping_sub(self, other: Self) -> Self;
+    fn wrapping_mul(self, other: Self) -> Self;
+    fn saturating_add(self, other: Self) -> Self;
+    fn saturating_sub(self, other: Self) -> Self;
+    fn saturating_mul(self, other: Self) -> Self;
+}
+
+// implementa Integer repassando para os metodos do proprio tipo
+macro_rules! impl_integer {
+    ($($t:ty => $wide:ty),*) => {
+        $(
+            impl Integer for $t {
+                type Wide = $wide;
+
+                fn checked_add(self, other: $t) -> Option<$t> {
+                    <$t>::checked_add(self, other)
+                }
+
+                fn checked_sub(self, other: $t) -> Option<$t> {
+                    <$t>::checked_sub(self, other)
+                }
+
+                fn checked_mul(self, other: $t) -> Option<$t> {
+                    <$t>::checked_mul(self, other)
+                }
+
+                fn wrapping_add(self, other: $t) -> $t {
+                    <$t>::wrapping_add(self, other)
+                }
+
+                fn wrapping_sub(self, other: $t) -> $t {
+                    <$t>::wrapping_sub(self, other)
+                }
+
+                fn wrapping_mul(self, other: $t) -> $t {
+                    <$t>::wrapping_mul(self, other)
+                }
+
+                fn saturating_add(self, other: $t) -> $t {
+                    <$t>::saturating_add(self, other)
+                }
+
+                fn saturating_sub(self, other: $t) -> $t {
+                    <$t>::saturating_sub(self, other)
+                }
+
+                fn saturating_mul(self, other: $t) -> $t {
+                    <$t>::saturating_mul(self, other)
+                }
+            }
+
+        )*
+    };
+}
+
+impl_integer!(i8 => i16, i16 => i32, i32 => i64, i64 => i128, u8 => u16, u16 => u32, u32 => u64, u64 => u128);
+
+// numero complexo re + im * i, com as partes em f64
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Complex {
+    pub re: f64,
+    pub im: f64,
+}
+
+impl Complex {
+    pub fn new(re: f64, im: f64) -> Complex {
+        Complex { re, im }
+    }
+}
+
+impl Add for Complex {
+    type Output = Complex;
+
+    fn add(self, other: Complex) -> Complex {
+        Complex::new(self.re + other.re, self.im + other.im)
+    }
+}
+
+impl AddAssign for Complex {
+    fn add_assign(&mut self, other: Complex) {
+        self.re += other.re;
+        self.im += other.im;
+    }
+}
+
+impl Sub for Complex {
+    type Output = Complex;
+
+    fn sub(self, other: Complex) -> Complex {
+        Complex::new(self.re - other.re, self.im - other.im)
+    }
+}
+
+// (a + bi)(c + di) = (ac - bd) + (ad + bc)i
+impl Mul for Complex {
+    type Output = Complex;
+
+    fn mul(self, other: Complex) -> Complex {
+        Complex::new(
+            self.re * other.re - self.im * other.im,
+            self.re * other.im + self.im * other.re,
+        )
+    }
+}
+
+impl Display for Complex {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.im < 0.0 {
+            write!(f, "{}-{}i", self.re, -self.im)
+        } else {
+            write!(f, "{}+{}i", self.re, self.im)
+        }
+    }
+}
+
+impl Numeric for Complex {
+    fn from_i32(value: i32) -> Complex {
+        Complex::new(value as f64, 0.0)
+    }
+
+    fn magnitude(self) -> f64 {
+        self.re.hypot(self.im)
+    }
+
+    fn distance(self, other: Complex) -> f64 {
+        (self - other).magnitude()
+    }
+
+    type Intermediate = Complex;
+
+    fn to_intermediate(self) -> Complex {
+        self
+    }
+
+    fn from_intermediate(value: Complex) -> Complex {
+        value
+    }
+}
+
+// maior diferenca, elemento a elemento, entre duas matrizes do mesmo tamanho
+pub fn max_abs_diff<T: Numeric>(a: &Matrix<T>, b: &Matrix<T>) -> f64 {
+    assert_eq!(a.shape(), b.shape(), "matrices must have the same shape");
+    a.as_slice()
+        .iter()
+        .zip(b.as_slice())
+        .map(|(&x, &y)| x.distance(y))
+        .fold(0.0, f64::max)
+}
+
+// compara duas matrizes com tolerancia, em vez de igualdade exata
+// com ponto flutuante a ordem das somas muda o resultado nos ultimos bits,
+// entao cada elemento pode diferir em ate tol vezes o seu tamanho (e pelo menos tol)
+pub fn approx_eq<T: Numeric>(a: &Matrix<T>, b: &Matrix<T>, tol: f64) -> bool {
+    a.shape() == b.shape()
+        && a.as_slice()
+            .iter()
+            .zip(b.as_slice())
+            .all(|(&x, &y)| x.distance(y) <= tol * x.magnitude().max(y.magnitude()).max(1.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matrix::MatrixError;
+    use crate::ops::{mul_matrix_sequential, mul_matrix_threads, sub_matrix_sequential, sub_matrix_threads, sum_matrix_sequential, sum_matrix_threads};
+    use crate::random::create_matrix_from_random;
+    use crate::testing::assert_threads_match;
+
+    // soma, subtracao e multiplicacao com threads dao o mesmo resultado da sequencial para o tipo T
+    // cada elemento e calculado na mesma ordem pelas duas versoes, entao a comparacao e exata mesmo com ponto flutuante
+    fn check_kernels<T: Numeric>(m1: &Matrix<T>, m2: &Matrix<T>) {
+        assert_threads_match(sum_matrix_sequential(m1, m2), |thr| sum_matrix_threads(m1, m2, thr));
+        assert_threads_match(sub_matrix_sequential(m1, m2), |thr| sub_matrix_threads(m1, m2, thr));
+        assert_threads_match(mul_matrix_sequential(m1, m2), |thr| mul_matrix_threads(m1, m2, thr));
+    }
+
+    #[test]
+    fn threads_match_sequential_for_every_type() -> Result<(), MatrixError> {
+        let a = create_matrix_from_random(9, 9, 1000);
+        let b = create_matrix_from_random(9, 9, 1000);
+        check_kernels(&a.map(|&v| v as f32 / 100.0), &b.map(|&v| v as f32 / 100.0));
+        check_kernels(&a.map(|&v| v as f64 / 100.0), &b.map(|&v| v as f64 / 100.0));
+        check_kernels(&a.map(|&v| i64::from_i32(v)), &b.map(|&v| -i64::from_i32(v)));
+
+        // u8 com 0 ou 1, e m1 - m1, para a multiplicacao nao passar de 255 nem a subtracao ficar negativa
+        let bits = create_matrix_from_random(9, 9, 2).map(|&v| u8::from_i32(v));
+        check_kernels(&bits, &bits);
+
+        let data = a.as_slice().iter().zip(b.as_slice()).map(|(&re, &im)| Complex::new(re as f64 / 100.0, -im as f64 / 100.0)).collect();
+        let c = Matrix::from_vec(9, 9, data)?;
+        check_kernels(&c, &c.map(|z| Complex::new(z.im, z.re)));
+        Ok(())
+    }
+
+    // a tolerancia e relativa ao maior dos dois valores, e absoluta abaixo de 1
+    #[test]
+    fn approx_eq_tolerance_limits() -> Result<(), MatrixError> {
+        let tol = 1e-6;
+        let one = |x: f64| Matrix::try_from(vec![vec![x]]);
+        assert!(approx_eq(&one(1000.0)?, &one(1000.0009)?, tol));
+        assert!(!approx_eq(&one(1000.0)?, &one(1000.0011)?, tol));
+        assert!(approx_eq(&one(0.0)?, &one(0.9e-6)?, tol));
+        assert!(!approx_eq(&one(0.0)?, &one(1.1e-6)?, tol));
+
+        // com complexos a distancia e o modulo da diferenca
+        let z = |re: f64, im: f64| Matrix::try_from(vec![vec![Complex::new(re, im)]]);
+        assert!(approx_eq(&z(0.0, 0.0)?, &z(0.6e-6, 0.6e-6)?, tol));
+        assert!(!approx_eq(&z(0.0, 0.0)?, &z(0.8e-6, 0.8e-6)?, tol));
+
+        // tamanhos diferentes nunca sao iguais
+        assert!(!approx_eq(&one(1.0)?, &Matrix::try_from(vec![vec![1.0, 1.0]])?, tol));
+        Ok(())
+    }
+
+    // a diferenca de tipos sem sinal nao estoura
+    #[test]
+    fn max_abs_diff_without_overflow() -> Result<(), MatrixError> {
+        let a = Matrix::try_from(vec![vec![0u8, 200], vec![7, 7]])?;
+        let b = Matrix::try_from(vec![vec![255u8, 100], vec![7, 8]])?;
+        assert_eq!(max_abs_diff(&a, &b), 255.0);
+        assert_eq!(max_abs_diff(&a, &a), 0.0);
+        Ok(())
+    }
+}
@@ -0,0 +1,955 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use crate::matrix::{Matrix, MatrixError};
+use crate::numeric::{Integer, Numeric};
+use crate::pool;
+
+// quantidade padrao de linhas que cada thread pega por vez no escalonamento dinamico
+pub const DEFAULT_CHUNK: usize = 4;
+
+// verifica se as duas matrizes tem o mesmo tamanho, usado pela soma e subtracao
+pub(crate) fn check_same_shape<T>(m1: &Matrix<T>, m2: &Matrix<T>, op: &'static str) -> Result<(), MatrixError> {
+    if m1.is_empty() || m2.is_empty() {
+        return Err(MatrixError::Empty);
+    }
+    if m1.shape() != m2.shape() {
+        return Err(MatrixError::ShapeMismatch { left: m1.shape(), right: m2.shape(), op });
+    }
+    Ok(())
+}
+
+// verifica se as colunas de m1 sao iguais as linhas de m2, usado pela multiplicacao
+pub(crate) fn check_mul_shape<T>(m1: &Matrix<T>, m2: &Matrix<T>, op: &'static str) -> Result<(), MatrixError> {
+    if m1.is_empty() || m2.is_empty() {
+        return Err(MatrixError::Empty);
+    }
+    if m1.cols() != m2.rows() {
+        return Err(MatrixError::ShapeMismatch { left: m1.shape(), right: m2.shape(), op });
+    }
+    Ok(())
+}
+
+// verifica se a matriz e quadrada, usado pelos grafos e pelas decomposicoes
+pub(crate) fn check_square<T>(m: &Matrix<T>, op: &'static str) -> Result<(), MatrixError> {
+    if m.is_empty() {
+        return Err(MatrixError::Empty);
+    }
+    if m.rows() != m.cols() {
+        return Err(MatrixError::NotSquare { rows: m.rows(), cols: m.cols(), op });
+    }
+    Ok(())
+}
+
+// divide a matriz de saida (rows x cols, em ordem de linhas) em thr partes, uma por trabalho enviado ao pool
+// cada parte recebe a linha onde comeca e um pedaco mutavel so dela, sem sobreposicao
+// thr e ajustado por pool::effective_threads, entao 0 (AUTO) ou mais threads do que linhas tambem funcionam,
+// e as linhas que sobram da divisao sao espalhadas entre as partes em vez de ficarem todas na ultima
+pub(crate) fn split_rows_mut<T>(out: &mut [T], rows: usize, cols: usize, thr: usize) -> Vec<(usize, &mut [T])> {
+    let thr = pool::effective_threads(thr, rows);
+    let mut parts = Vec::new();
+    let mut rest = out;
+    for i in 0..thr {
+        let start = i * rows / thr;
+        let end = (i + 1) * rows / thr;
+        let (chunk, tail) = rest.split_at_mut((end - start) * cols);
+        parts.push((start, chunk));
+        rest = tail;
+    }
+    parts
+}
+
+// pedaco da saida que ainda nao foi pego por nenhuma thread
+type Slot<'a, T> = Mutex<Option<(usize, &'a mut [T])>>;
+
+// escalonamento dinamico: divide a saida em pedacos de chunk linhas e as thr threads pegam o proximo
+// pedaco livre atraves de um contador atomico compartilhado, ate nao sobrar nenhum
+// assim uma thread que termina antes continua trabalhando, em vez de ficar parada esperando a mais lenta
+// f recebe a linha onde o pedaco comeca e o pedaco mutavel da saida
+fn run_rows_dynamic<T, F>(out: &mut [T], rows: usize, cols: usize, thr: usize, chunk: usize, f: F)
+where
+    T: Send,
+    F: Fn(usize, &mut [T]) + Sync,
+{
+    let chunk = chunk.max(1);
+    // cada pedaco fica em um Mutex<Option<..>> so para poder ser retirado por uma unica thread
+    // o contador garante que cada indice e pego uma vez, entao os locks nunca disputam entre si
+    let slots: Vec<Slot<T>> = out
+        .chunks_mut(chunk * cols)
+        .enumerate()
+        .map(|(n, part)| Mutex::new(Some((n * chunk, part))))
+        .collect();
+    let next = AtomicUsize::new(0);
+    // nao adianta ter mais threads do que pedacos
+    let workers = pool::effective_threads(thr, rows.div_ceil(chunk));
+
+    pool::global().scope(|s| {
+        for _ in 0..workers {
+            s.spawn(|| loop {
+                let n = next.fetch_add(1, Ordering::Relaxed);
+                if n >= slots.len() {
+                    break;
+                }
+                if let Some((start, part)) = slots[n].lock().unwrap().take() {
+                    f(start, part);
+                }
+            });
+        }
+    });
+}
+
+// faz a soma entre 2 matrizes de item i, j da m1 e item i, j da m2
+// recebe m1 e m2 como emprestimo, pois nao precisa modificar as matrizes originais
+pub fn sum_matrix_sequential<T: Numeric>(m1: &Matrix<T>, m2: &Matrix<T>) -> Result<Matrix<T>, MatrixError> {
+    check_same_shape(m1, m2, "sum")?;
+    // matriz que sera retornada no final
+    let mut result = Matrix::new(m1.rows(), m1.cols());
+    // percorre as linhas da matriz
+    for i in 0..m1.rows() {
+        for j in 0..m1.cols() {
+            // soma os valores das matrizes
+            result[(i, j)] = m1[(i, j)] + m2[(i, j)];
+        }
+    }
+    Ok(result)
+}
+
+// faz a soma entre 2 matrizes de item i, j da m1 e item i, j da m2 usando threads
+// as threads pegam m1 e m2 emprestados e escrevem direto na sua parte da matriz resultante
+pub fn sum_matrix_threads<T: Numeric>(m1: &Matrix<T>, m2: &Matrix<T>, thr: usize) -> Result<Matrix<T>, MatrixError> {
+    check_same_shape(m1, m2, "sum")?;
+    // rows e cols determinam o tamanho da matriz resultante, que ja e alocada inteira aqui
+    let rows = m1.rows();
+    let cols = m1.cols();
+    let mut result = Matrix::new(rows, cols);
+
+    // o scope do pool garante que todas as partes terminam antes de sair do bloco,
+    // por isso elas podem usar m1 e m2 sem clonar, e as threads do pool sao reaproveitadas entre chamadas
+    pool::global().scope(|s| {
+        for (start, chunk) in split_rows_mut(result.as_mut_slice(), rows, cols, thr) {
+            s.spawn(move || {
+                // faz todo o processo, igual na funcao sequencial, linha por linha do pedaco da thread
+                for (r, out) in chunk.chunks_mut(cols).enumerate() {
+                    let a = m1.row(start + r);
+                    let b = m2.row(start + r);
+                    for j in 0..cols {
+                        out[j] = a[j] + b[j];
+                    }
+                }
+            });
+        }
+    });
+    // retorna o resultado final
+    Ok(result)
+}
+
+// faz a subtraco entre 2 matrizes de item i, j da m1 e item i, j da m2
+pub fn sub_matrix_sequential<T: Numeric>(m1: &Matrix<T>, m2: &Matrix<T>) -> Result<Matrix<T>, MatrixError> {
+    check_same_shape(m1, m2, "sub")?;
+    // tem o mesmo funcionamento que a funcao de soma, apenas com subtracao
+    let mut result = Matrix::new(m1.rows(), m1.cols());
+    for i in 0..m1.rows() {
+        for j in 0..m1.cols() {
+            result[(i, j)] = m1[(i, j)] - m2[(i, j)];
+        }
+    }
+    Ok(result)
+}
+
+// faz a subtraco entre 2 matrizes de item i, j da m1 e item i, j da m2 usando threads
+pub fn sub_matrix_threads<T: Numeric>(m1: &Matrix<T>, m2: &Matrix<T>, thr: usize) -> Result<Matrix<T>, MatrixError> {
+    check_same_shape(m1, m2, "sub")?;
+    // mesmo funcionamento que a funcao de soma, apenas com subtracao
+    let rows = m1.rows();
+    let cols = m1.cols();
+    let mut result = Matrix::new(rows, cols);
+
+    pool::global().scope(|s| {
+        for (start, chunk) in split_rows_mut(result.as_mut_slice(), rows, cols, thr) {
+            s.spawn(move || {
+                for (r, out) in chunk.chunks_mut(cols).enumerate() {
+                    let a = m1.row(start + r);
+                    let b = m2.row(start + r);
+                    for j in 0..cols {
+                        out[j] = a[j] - b[j];
+                    }
+                }
+            });
+        }
+    });
+    Ok(result)
+}
+
+// faz a multiplicacao entre 2 matrizes de soma de linhas por colunas de m1 e m2 no item i, j
+pub fn mul_matrix_sequential<T: Numeric>(m1: &Matrix<T>, m2: &Matrix<T>) -> Result<Matrix<T>, MatrixError> {
+    check_mul_shape(m1, m2, "mul")?;
+    let mut result = Matrix::new(m1.rows(), m2.cols());
+    for i in 0..m1.rows() {
+        for j in 0..m2.cols() {
+            // percorre todas as linhas e colunas, somando o valor da multiplicacao de linhas por coluna
+            let mut sum = T::default();
+            for k in 0..m1.cols() {
+                // percore todas as colunas de m1 e todas as linhas de m2
+                sum += m1[(i, k)] * m2[(k, j)];
+            }
+            result[(i, j)] = sum;
+        }
+    }
+    Ok(result)
+}
+
+// faz a multiplicacao entre 2 matrizes de soma de linhas por colunas de m1 e m2 no item i, j usando threads
+pub fn mul_matrix_threads<T: Numeric>(m1: &Matrix<T>, m2: &Matrix<T>, thr: usize) -> Result<Matrix<T>, MatrixError> {
+    check_mul_shape(m1, m2, "mul")?;
+    // tem o mesmo funcionamento que a funcao de multiplicacao, apenas com threads
+    let rows = m1.rows();
+    let cols = m2.cols();
+    let inner = m1.cols();
+    let mut result = Matrix::new(rows, cols);
+
+    pool::global().scope(|s| {
+        for (start, chunk) in split_rows_mut(result.as_mut_slice(), rows, cols, thr) {
+            s.spawn(move || {
+                for (r, out) in chunk.chunks_mut(cols).enumerate() {
+                    let a = m1.row(start + r);
+                    for (j, x) in out.iter_mut().enumerate() {
+                        let mut sum = T::default();
+                        for k in 0..inner {
+                            sum += a[k] * m2[(k, j)];
+                        }
+                        *x = sum;
+                    }
+                }
+            });
+        }
+    });
+    Ok(result)
+}
+
+// soma usando escalonamento dinamico, as threads pegam chunk linhas por vez
+pub fn sum_matrix_dynamic<T: Numeric>(m1: &Matrix<T>, m2: &Matrix<T>, thr: usize, chunk: usize) -> Result<Matrix<T>, MatrixError> {
+    check_same_shape(m1, m2, "sum")?;
+    let (rows, cols) = m1.shape();
+    let mut result = Matrix::new(rows, cols);
+
+    run_rows_dynamic(result.as_mut_slice(), rows, cols, thr, chunk, |start, part| {
+        for (r, out) in part.chunks_mut(cols).enumerate() {
+            let a = m1.row(start + r);
+            let b = m2.row(start + r);
+            for j in 0..cols {
+                out[j] = a[j] + b[j];
+            }
+        }
+    });
+    Ok(result)
+}
+
+// subtracao usando escalonamento dinamico
+pub fn sub_matrix_dynamic<T: Numeric>(m1: &Matrix<T>, m2: &Matrix<T>, thr: usize, chunk: usize) -> Result<Matrix<T>, MatrixError> {
+    check_same_shape(m1, m2, "sub")?;
+    let (rows, cols) = m1.shape();
+    let mut result = Matrix::new(rows, cols);
+
+    run_rows_dynamic(result.as_mut_slice(), rows, cols, thr, chunk, |start, part| {
+        for (r, out) in part.chunks_mut(cols).enumerate() {
+            let a = m1.row(start + r);
+            let b = m2.row(start + r);
+            for j in 0..cols {
+                out[j] = a[j] - b[j];
+            }
+        }
+    });
+    Ok(result)
+}
+
+// multiplicacao usando escalonamento dinamico, cada pedaco usa o mesmo kernel em blocos da versao blocked
+pub fn mul_matrix_dynamic<T: Numeric>(m1: &Matrix<T>, m2: &Matrix<T>, thr: usize, chunk: usize) -> Result<Matrix<T>, MatrixError> {
+    check_mul_shape(m1, m2, "mul")?;
+    let rows = m1.rows();
+    let cols = m2.cols();
+    let mut result = Matrix::new(rows, cols);
+
+    run_rows_dynamic(result.as_mut_slice(), rows, cols, thr, chunk, |start, part| {
+        mul_blocked_rows(m1, m2, start, part, DEFAULT_TILE);
+    });
+    Ok(result)
+}
+
+// como mul_matrix_partitioned divide o trabalho entre as threads
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Partition {
+    // cada thread fica com um grupo de linhas de m1, igual a mul_matrix_threads
+    Rows,
+    // a saida e dividida em blocos 2D de tile x tile, que sao distribuidos entre as threads
+    // com k_splits > 1 a dimensao k (colunas de m1 / linhas de m2) tambem e dividida,
+    // e as somas parciais de cada bloco sao somadas no final, tambem dividido entre as threads por bloco
+    Tiles { tile: usize, k_splits: usize },
+}
+
+// um bloco da saida: comeca na linha i0 e coluna j0, com um pedaco mutavel de cada uma das suas linhas
+// os pedacos de blocos diferentes nao se sobrepoem, entao cada thread escreve direto no seu bloco
+struct OutTile<'a, T> {
+    i0: usize,
+    j0: usize,
+    rows: Vec<&'a mut [T]>,
+}
+
+// divide a saida (rows x cols) em blocos de tile x tile, em ordem de linhas de blocos
+// cada linha da saida e cortada nas fronteiras das colunas dos blocos, e cada pedaco vai para o seu bloco
+fn split_tiles_mut<T>(out: &mut [T], cols: usize, tile: usize) -> Vec<OutTile<'_, T>> {
+    let mut tiles = Vec::new();
+    for (band, rows) in out.chunks_mut(tile * cols).enumerate() {
+        let first = tiles.len();
+        for j0 in (0..cols).step_by(tile) {
+            tiles.push(OutTile { i0: band * tile, j0, rows: Vec::new() });
+        }
+        for row in rows.chunks_mut(cols) {
+            let mut rest = row;
+            for t in &mut tiles[first..] {
+                let (segment, tail) = rest.split_at_mut(tile.min(rest.len()));
+                t.rows.push(segment);
+                rest = tail;
+            }
+        }
+    }
+    tiles
+}
+
+// roda f em cada bloco com thr threads, que pegam o proximo bloco livre por um contador atomico
+fn run_tiles<T, F>(tiles: Vec<OutTile<'_, T>>, thr: usize, f: F)
+where
+    T: Send,
+    F: Fn(usize, OutTile<'_, T>) + Sync,
+{
+    let count = tiles.len();
+    let slots: Vec<Mutex<Option<OutTile<T>>>> = tiles.into_iter().map(|t| Mutex::new(Some(t))).collect();
+    let next = AtomicUsize::new(0);
+    pool::global().scope(|s| {
+        for _ in 0..pool::effective_threads(thr, count) {
+            s.spawn(|| loop {
+                let n = next.fetch_add(1, Ordering::Relaxed);
+                if n >= count {
+                    break;
+                }
+                if let Some(tile) = slots[n].lock().unwrap().take() {
+                    f(n, tile);
+                }
+            });
+        }
+    });
+}
+
+// soma em out[(i, j)] os produtos a[k] * b[(k, j)] com k de k0 ate k1, para as linhas e colunas do bloco
+// mesma ordem i-k-j da multiplicacao em blocos
+fn mul_tile<T: Numeric>(m1: &Matrix<T>, m2: &Matrix<T>, i0: usize, j0: usize, k0: usize, k1: usize, rows: &mut [&mut [T]]) {
+    for (r, out) in rows.iter_mut().enumerate() {
+        let a = m1.row(i0 + r);
+        for (k, &aik) in a.iter().enumerate().take(k1).skip(k0) {
+            let b = &m2.row(k)[j0..j0 + out.len()];
+            for (x, &bkj) in out.iter_mut().zip(b) {
+                *x += aik * bkj;
+            }
+        }
+    }
+}
+
+// multiplicacao usando threads, com a forma de dividir o trabalho escolhida por partition
+pub fn mul_matrix_partitioned<T: Numeric>(m1: &Matrix<T>, m2: &Matrix<T>, thr: usize, partition: Partition) -> Result<Matrix<T>, MatrixError> {
+    let (tile, k_splits) = match partition {
+        Partition::Rows => return mul_matrix_threads(m1, m2, thr),
+        Partition::Tiles { tile, k_splits } => (tile.max(1), k_splits.max(1)),
+    };
+    check_mul_shape(m1, m2, "mul")?;
+    let rows = m1.rows();
+    let cols = m2.cols();
+    let inner = m1.cols();
+    // nao da para dividir k em mais partes do que o proprio k
+    let k_splits = k_splits.min(inner);
+    let mut result = Matrix::new(rows, cols);
+
+    if k_splits == 1 {
+        // cada bloco e calculado direto na sua parte da saida
+        run_tiles(split_tiles_mut(result.as_mut_slice(), cols, tile), thr, |_, mut t| {
+            mul_tile(m1, m2, t.i0, t.j0, 0, inner, &mut t.rows);
+        });
+        return Ok(result);
+    }
+
+    // com k dividido, primeiro cada (bloco, parte de k) calcula a sua soma parcial no seu proprio espaco,
+    // depois cada bloco soma as suas partes, em ordem, direto na saida; as duas fases sao divididas entre as threads
+    let tiles = split_tiles_mut(result.as_mut_slice(), cols, tile);
+    let bounds: Vec<(usize, usize, usize, usize)> = tiles.iter().map(|t| (t.i0, t.j0, t.rows.len(), t.rows[0].len())).collect();
+    let partials: Vec<Mutex<Vec<T>>> = (0..bounds.len() * k_splits).map(|_| Mutex::new(Vec::new())).collect();
+    let next = AtomicUsize::new(0);
+    pool::global().scope(|s| {
+        for _ in 0..pool::effective_threads(thr, partials.len()) {
+            s.spawn(|| loop {
+                let n = next.fetch_add(1, Ordering::Relaxed);
+                if n >= partials.len() {
+                    break;
+                }
+                let (i0, j0, height, width) = bounds[n / k_splits];
+                let p = n % k_splits;
+                let mut block = vec![T::default(); height * width];
+                let mut block_rows: Vec<&mut [T]> = block.chunks_mut(width).collect();
+                mul_tile(m1, m2, i0, j0, p * inner / k_splits, (p + 1) * inner / k_splits, &mut block_rows);
+                *partials[n].lock().unwrap() = block;
+            });
+        }
+    });
+    run_tiles(tiles, thr, |n, mut t| {
+        let width = t.rows[0].len();
+        for p in 0..k_splits {
+            let block = partials[n * k_splits + p].lock().unwrap();
+            for (out, part) in t.rows.iter_mut().zip(block.chunks(width)) {
+                for (x, &y) in out.iter_mut().zip(part) {
+                    *x += y;
+                }
+            }
+        }
+    });
+    Ok(result)
+}
+
+// tamanho padrao do bloco (tile) da multiplicacao em blocos, 64x64 inteiros cabem com folga no cache L1/L2
+pub const DEFAULT_TILE: usize = 64;
+
+// calcula as linhas start.. da multiplicacao m1 * m2 e escreve em out (que tem out.len() / cols linhas)
+// percorre a matriz em blocos de tile x tile e usa a ordem i-k-j no laco interno,
+// assim m2 e out sao lidos linha por linha, em memoria continua, em vez de coluna por coluna
+fn mul_blocked_rows<T: Numeric>(m1: &Matrix<T>, m2: &Matrix<T>, start: usize, out: &mut [T], tile: usize) {
+    let cols = m2.cols();
+    let inner = m1.cols();
+    let rows = out.len() / cols;
+
+    for ii in (0..rows).step_by(tile) {
+        let i_end = (ii + tile).min(rows);
+        for kk in (0..inner).step_by(tile) {
+            let k_end = (kk + tile).min(inner);
+            for jj in (0..cols).step_by(tile) {
+                let j_end = (jj + tile).min(cols);
+                for i in ii..i_end {
+                    let a = m1.row(start + i);
+                    let out_row = &mut out[i * cols..(i + 1) * cols];
+                    for (k, &aik) in a.iter().enumerate().take(k_end).skip(kk) {
+                        let b = m2.row(k);
+                        for j in jj..j_end {
+                            out_row[j] += aik * b[j];
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+// multiplicacao em blocos (tiles) de tamanho tile, o resultado e o mesmo da mul_matrix_sequential
+pub fn mul_matrix_blocked_sequential<T: Numeric>(m1: &Matrix<T>, m2: &Matrix<T>, tile: usize) -> Result<Matrix<T>, MatrixError> {
+    check_mul_shape(m1, m2, "mul_blocked")?;
+    let mut result = Matrix::new(m1.rows(), m2.cols());
+    // bloco de tamanho 0 nao faz sentido, usamos pelo menos 1
+    mul_blocked_rows(m1, m2, 0, result.as_mut_slice(), tile.max(1));
+    Ok(result)
+}
+
+// multiplicacao em blocos usando threads, cada parte do pool calcula um grupo de linhas em blocos
+pub fn mul_matrix_blocked_threads<T: Numeric>(m1: &Matrix<T>, m2: &Matrix<T>, tile: usize, thr: usize) -> Result<Matrix<T>, MatrixError> {
+    check_mul_shape(m1, m2, "mul_blocked")?;
+    let rows = m1.rows();
+    let cols = m2.cols();
+    let tile = tile.max(1);
+    let mut result = Matrix::new(rows, cols);
+
+    pool::global().scope(|s| {
+        for (start, chunk) in split_rows_mut(result.as_mut_slice(), rows, cols, thr) {
+            s.spawn(move || mul_blocked_rows(m1, m2, start, chunk, tile));
+        }
+    });
+    Ok(result)
+}
+
+// o que fazer quando uma conta com inteiros passa do limite do tipo
+// o modo vale para cada soma e cada produto, inclusive as somas parciais da multiplicacao
+// para fazer as contas sem estouro, use as versoes *_widened, que fazem as contas no tipo com o dobro de bits
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Overflow {
+    // retorna MatrixError::Overflow com a posicao do primeiro elemento que estourou
+    Checked,
+    // da a volta, como os inteiros fazem em release
+    Wrapping,
+    // fica parado no maior ou no menor valor do tipo
+    Saturating,
+}
+
+impl Overflow {
+    // soma de dois elementos no modo escolhido, None quando o resultado nao cabe no tipo
+    fn add<T: Integer>(self, a: T, b: T) -> Option<T> {
+        match self {
+            Overflow::Checked => a.checked_add(b),
+            Overflow::Wrapping => Some(a.wrapping_add(b)),
+            Overflow::Saturating => Some(a.saturating_add(b)),
+        }
+    }
+
+    // subtracao de dois elementos no modo escolhido
+    fn sub<T: Integer>(self, a: T, b: T) -> Option<T> {
+        match self {
+            Overflow::Checked => a.checked_sub(b),
+            Overflow::Wrapping => Some(a.wrapping_sub(b)),
+            Overflow::Saturating => Some(a.saturating_sub(b)),
+        }
+    }
+
+    // acc + a * b no modo escolhido, o passo das multiplicacoes
+    fn mul_add<T: Integer>(self, acc: T, a: T, b: T) -> Option<T> {
+        match self {
+            Overflow::Checked => acc.checked_add(a.checked_mul(b)?),
+            Overflow::Wrapping => Some(acc.wrapping_add(a.wrapping_mul(b))),
+            Overflow::Saturating => Some(acc.saturating_add(a.saturating_mul(b))),
+        }
+    }
+}
+
+// converte a matriz para o tipo com o dobro de bits, ex.: de Matrix<i32> para Matrix<i64>
+// no tipo maior o produto de dois elementos sempre cabe, so somas de muitos produtos grandes ainda estouram
+pub fn widen<T: Integer>(m: &Matrix<T>) -> Matrix<T::Wide> {
+    m.map(|&x| T::Wide::from(x))
+}
+
+// como build_with divide as linhas da saida: sem threads, em partes iguais ou com escalonamento dinamico
+#[derive(Debug, Clone, Copy)]
+enum Schedule {
+    Sequential,
+    Threads(usize),
+    Dynamic(usize, usize),
+}
+
+// preenche as linhas start.. de out com f(i, j), parando no primeiro elemento que estourar
+fn fill_rows_with<T, F>(out: &mut [T], start: usize, cols: usize, f: &F) -> Result<(), (usize, usize)>
+where
+    F: Fn(usize, usize) -> Option<T>,
+{
+    for (r, row) in out.chunks_mut(cols).enumerate() {
+        for (j, x) in row.iter_mut().enumerate() {
+            *x = f(start + r, j).ok_or((start + r, j))?;
+        }
+    }
+    Ok(())
+}
+
+// kernel em blocos da mul_blocked_rows com cada conta no modo escolhido
+// em cada elemento os k continuam em ordem crescente, entao os elementos que estouram sao os mesmos
+// da multiplicacao linha por coluna; um elemento que estourou para de ser atualizado
+fn mul_blocked_rows_with<T: Integer>(m1: &Matrix<T>, m2: &Matrix<T>, start: usize, out: &mut [T], tile: usize, mode: Overflow) -> Result<(), (usize, usize)> {
+    let cols = m2.cols();
+    let inner = m1.cols();
+    let rows = out.len() / cols;
+    let mut overflowed = vec![false; out.len()];
+
+    for ii in (0..rows).step_by(tile) {
+        let i_end = (ii + tile).min(rows);
+        for kk in (0..inner).step_by(tile) {
+            let k_end = (kk + tile).min(inner);
+            for jj in (0..cols).step_by(tile) {
+                let j_end = (jj + tile).min(cols);
+                for i in ii..i_end {
+                    let a = m1.row(start + i);
+                    let out_row = &mut out[i * cols + jj..i * cols + j_end];
+                    let failed = &mut overflowed[i * cols + jj..i * cols + j_end];
+                    for (k, &aik) in a.iter().enumerate().take(k_end).skip(kk) {
+                        let b = &m2.row(k)[jj..j_end];
+                        for ((x, failed), &bkj) in out_row.iter_mut().zip(failed.iter_mut()).zip(b) {
+                            if *failed {
+                                continue;
+                            }
+                            match mode.mul_add(*x, aik, bkj) {
+                                Some(value) => *x = value,
+                                None => *failed = true,
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+    match overflowed.iter().position(|&o| o) {
+        Some(n) => Err((start + n / cols, n % cols)),
+        None => Ok(()),
+    }
+}
+
+// monta a matriz rows x cols com fill(start, linhas), que devolve a posicao do primeiro estouro das suas linhas
+// com threads, cada parte para no seu primeiro estouro e o erro reportado e o de menor posicao,
+// o mesmo que a versao sequencial encontraria
+fn build_with<T, F>(rows: usize, cols: usize, schedule: Schedule, op: &'static str, fill: F) -> Result<Matrix<T>, MatrixError>
+where
+    T: Numeric,
+    F: Fn(usize, &mut [T]) -> Result<(), (usize, usize)> + Sync,
+{
+    let mut result = Matrix::new(rows, cols);
+    let first_error = Mutex::new(None);
+    let run = |start, part: &mut [T]| {
+        if let Err(at) = fill(start, part) {
+            let mut first = first_error.lock().unwrap();
+            if first.is_none_or(|prev| at < prev) {
+                *first = Some(at);
+            }
+        }
+    };
+    match schedule {
+        Schedule::Sequential => run(0, result.as_mut_slice()),
+        Schedule::Threads(thr) => pool::global().scope(|s| {
+            for (start, chunk) in split_rows_mut(result.as_mut_slice(), rows, cols, thr) {
+                let run = &run;
+                s.spawn(move || run(start, chunk));
+            }
+        }),
+        Schedule::Dynamic(thr, chunk) => run_rows_dynamic(result.as_mut_slice(), rows, cols, thr, chunk, run),
+    }
+    match first_error.into_inner().unwrap() {
+        Some((row, col)) => Err(MatrixError::Overflow { row, col, op }),
+        None => Ok(result),
+    }
+}
+
+// soma ou subtracao elemento a elemento, com apply fazendo a conta de cada par de elementos
+fn elementwise_with<T, F>(m1: &Matrix<T>, m2: &Matrix<T>, schedule: Schedule, op: &'static str, apply: F) -> Result<Matrix<T>, MatrixError>
+where
+    T: Integer,
+    F: Fn(T, T) -> Option<T> + Sync,
+{
+    check_same_shape(m1, m2, op)?;
+    let cols = m1.cols();
+    let f = |i, j| apply(m1[(i, j)], m2[(i, j)]);
+    build_with(m1.rows(), cols, schedule, op, |start, part| fill_rows_with(part, start, cols, &f))
+}
+
+// produto da linha i de m1 pela coluna j de m2 no modo escolhido, na ordem de k da mul_matrix_sequential
+fn mul_with<T: Integer>(m1: &Matrix<T>, m2: &Matrix<T>, schedule: Schedule, mode: Overflow) -> Result<Matrix<T>, MatrixError> {
+    check_mul_shape(m1, m2, "mul")?;
+    let cols = m2.cols();
+    let dot = |i, j| m1.row(i).iter().enumerate().try_fold(T::default(), |sum, (k, &x)| mode.mul_add(sum, x, m2[(k, j)]));
+    build_with(m1.rows(), cols, schedule, "mul", |start, part| fill_rows_with(part, start, cols, &dot))
+}
+
+// multiplicacao em blocos no modo escolhido, com o mesmo resultado e o mesmo erro da mul_with
+fn mul_blocked_with<T: Integer>(m1: &Matrix<T>, m2: &Matrix<T>, tile: usize, schedule: Schedule, mode: Overflow) -> Result<Matrix<T>, MatrixError> {
+    check_mul_shape(m1, m2, "mul_blocked")?;
+    let tile = tile.max(1);
+    build_with(m1.rows(), m2.cols(), schedule, "mul", |start, part| mul_blocked_rows_with(m1, m2, start, part, tile, mode))
+}
+
+// as versoes *_with de cada kernel fazem as mesmas contas no modo mode
+// para um mesmo modo todas dao o mesmo resultado, e no modo Checked o mesmo erro, da versao sequencial
+
+// soma com o tratamento de estouro escolhido por mode
+pub fn sum_matrix_sequential_with<T: Integer>(m1: &Matrix<T>, m2: &Matrix<T>, mode: Overflow) -> Result<Matrix<T>, MatrixError> {
+    elementwise_with(m1, m2, Schedule::Sequential, "sum", |a, b| mode.add(a, b))
+}
+
+pub fn sum_matrix_threads_with<T: Integer>(m1: &Matrix<T>, m2: &Matrix<T>, thr: usize, mode: Overflow) -> Result<Matrix<T>, MatrixError> {
+    elementwise_with(m1, m2, Schedule::Threads(thr), "sum", |a, b| mode.add(a, b))
+}
+
+pub fn sum_matrix_dynamic_with<T: Integer>(m1: &Matrix<T>, m2: &Matrix<T>, thr: usize, chunk: usize, mode: Overflow) -> Result<Matrix<T>, MatrixError> {
+    elementwise_with(m1, m2, Schedule::Dynamic(thr, chunk), "sum", |a, b| mode.add(a, b))
+}
+
+// subtracao com o tratamento de estouro escolhido por mode
+pub fn sub_matrix_sequential_with<T: Integer>(m1: &Matrix<T>, m2: &Matrix<T>, mode: Overflow) -> Result<Matrix<T>, MatrixError> {
+    elementwise_with(m1, m2, Schedule::Sequential, "sub", |a, b| mode.sub(a, b))
+}
+
+pub fn sub_matrix_threads_with<T: Integer>(m1: &Matrix<T>, m2: &Matrix<T>, thr: usize, mode: Overflow) -> Result<Matrix<T>, MatrixError> {
+    elementwise_with(m1, m2, Schedule::Threads(thr), "sub", |a, b| mode.sub(a, b))
+}
+
+pub fn sub_matrix_dynamic_with<T: Integer>(m1: &Matrix<T>, m2: &Matrix<T>, thr: usize, chunk: usize, mode: Overflow) -> Result<Matrix<T>, MatrixError> {
+    elementwise_with(m1, m2, Schedule::Dynamic(thr, chunk), "sub", |a, b| mode.sub(a, b))
+}
+
+// multiplicacao com o tratamento de estouro escolhido por mode
+pub fn mul_matrix_sequential_with<T: Integer>(m1: &Matrix<T>, m2: &Matrix<T>, mode: Overflow) -> Result<Matrix<T>, MatrixError> {
+    mul_with(m1, m2, Schedule::Sequential, mode)
+}
+
+pub fn mul_matrix_threads_with<T: Integer>(m1: &Matrix<T>, m2: &Matrix<T>, thr: usize, mode: Overflow) -> Result<Matrix<T>, MatrixError> {
+    mul_with(m1, m2, Schedule::Threads(thr), mode)
+}
+
+pub fn mul_matrix_dynamic_with<T: Integer>(m1: &Matrix<T>, m2: &Matrix<T>, thr: usize, chunk: usize, mode: Overflow) -> Result<Matrix<T>, MatrixError> {
+    mul_blocked_with(m1, m2, DEFAULT_TILE, Schedule::Dynamic(thr, chunk), mode)
+}
+
+pub fn mul_matrix_blocked_sequential_with<T: Integer>(m1: &Matrix<T>, m2: &Matrix<T>, tile: usize, mode: Overflow) -> Result<Matrix<T>, MatrixError> {
+    mul_blocked_with(m1, m2, tile, Schedule::Sequential, mode)
+}
+
+pub fn mul_matrix_blocked_threads_with<T: Integer>(m1: &Matrix<T>, m2: &Matrix<T>, tile: usize, thr: usize, mode: Overflow) -> Result<Matrix<T>, MatrixError> {
+    mul_blocked_with(m1, m2, tile, Schedule::Threads(thr), mode)
+}
+
+// soma ou subtracao elemento a elemento no tipo maior, com apply fazendo a conta de cada par ja convertido
+fn elementwise_widened<T, F>(m1: &Matrix<T>, m2: &Matrix<T>, schedule: Schedule, op: &'static str, apply: F) -> Result<Matrix<T::Wide>, MatrixError>
+where
+    T: Integer,
+    T::Wide: Integer,
+    F: Fn(T::Wide, T::Wide) -> Option<T::Wide> + Sync,
+{
+    check_same_shape(m1, m2, op)?;
+    let cols = m1.cols();
+    let f = |i, j| apply(T::Wide::from(m1[(i, j)]), T::Wide::from(m2[(i, j)]));
+    build_with(m1.rows(), cols, schedule, op, |start, part| fill_rows_with(part, start, cols, &f))
+}
+
+// produto da linha i de m1 pela coluna j de m2 acumulado no tipo maior, na ordem de k da mul_matrix_sequential
+fn mul_widened<T>(m1: &Matrix<T>, m2: &Matrix<T>, schedule: Schedule) -> Result<Matrix<T::Wide>, MatrixError>
+where
+    T: Integer,
+    T::Wide: Integer,
+{
+    check_mul_shape(m1, m2, "mul")?;
+    let cols = m2.cols();
+    let dot = |i, j| {
+        m1.row(i).iter().enumerate().try_fold(T::Wide::default(), |sum, (k, &x)| Overflow::Checked.mul_add(sum, T::Wide::from(x), T::Wide::from(m2[(k, j)])))
+    };
+    build_with(m1.rows(), cols, schedule, "mul", |start, part| fill_rows_with(part, start, cols, &dot))
+}
+
+// as versoes *_widened acumulam no tipo com o dobro de bits e retornam a matriz nesse tipo,
+// ex.: Matrix<i64> para entradas Matrix<i32>, sem converter as entradas inteiras antes como widen
+// a soma e a subtracao nunca estouram no tipo maior, e na multiplicacao cada produto cabe;
+// se a soma dos produtos ainda passar do limite, o erro e o mesmo do modo Checked
+
+// soma no tipo maior
+pub fn sum_matrix_sequential_widened<T: Integer>(m1: &Matrix<T>, m2: &Matrix<T>) -> Result<Matrix<T::Wide>, MatrixError>
+where
+    T::Wide: Integer,
+{
+    elementwise_widened(m1, m2, Schedule::Sequential, "sum", |a, b| a.checked_add(b))
+}
+
+pub fn sum_matrix_threads_widened<T: Integer>(m1: &Matrix<T>, m2: &Matrix<T>, thr: usize) -> Result<Matrix<T::Wide>, MatrixError>
+where
+    T::Wide: Integer,
+{
+    elementwise_widened(m1, m2, Schedule::Threads(thr), "sum", |a, b| a.checked_add(b))
+}
+
+pub fn sum_matrix_dynamic_widened<T: Integer>(m1: &Matrix<T>, m2: &Matrix<T>, thr: usize, chunk: usize) -> Result<Matrix<T::Wide>, MatrixError>
+where
+    T::Wide: Integer,
+{
+    elementwise_widened(m1, m2, Schedule::Dynamic(thr, chunk), "sum", |a, b| a.checked_add(b))
+}
+
+// subtracao no tipo maior
+pub fn sub_matrix_sequential_widened<T: Integer>(m1: &Matrix<T>, m2: &Matrix<T>) -> Result<Matrix<T::Wide>, MatrixError>
+where
+    T::Wide: Integer,
+{
+    elementwise_widened(m1, m2, Schedule::Sequential, "sub", |a, b| a.checked_sub(b))
+}
+
+pub fn sub_matrix_threads_widened<T: Integer>(m1: &Matrix<T>, m2: &Matrix<T>, thr: usize) -> Result<Matrix<T::Wide>, MatrixError>
+where
+    T::Wide: Integer,
+{
+    elementwise_widened(m1, m2, Schedule::Threads(thr), "sub", |a, b| a.checked_sub(b))
+}
+
+pub fn sub_matrix_dynamic_widened<T: Integer>(m1: &Matrix<T>, m2: &Matrix<T>, thr: usize, chunk: usize) -> Result<Matrix<T::Wide>, MatrixError>
+where
+    T::Wide: Integer,
+{
+    elementwise_widened(m1, m2, Schedule::Dynamic(thr, chunk), "sub", |a, b| a.checked_sub(b))
+}
+
+// multiplicacao acumulando no tipo maior
+pub fn mul_matrix_sequential_widened<T: Integer>(m1: &Matrix<T>, m2: &Matrix<T>) -> Result<Matrix<T::Wide>, MatrixError>
+where
+    T::Wide: Integer,
+{
+    mul_widened(m1, m2, Schedule::Sequential)
+}
+
+pub fn mul_matrix_threads_widened<T: Integer>(m1: &Matrix<T>, m2: &Matrix<T>, thr: usize) -> Result<Matrix<T::Wide>, MatrixError>
+where
+    T::Wide: Integer,
+{
+    mul_widened(m1, m2, Schedule::Threads(thr))
+}
+
+pub fn mul_matrix_dynamic_widened<T: Integer>(m1: &Matrix<T>, m2: &Matrix<T>, thr: usize, chunk: usize) -> Result<Matrix<T::Wide>, MatrixError>
+where
+    T::Wide: Integer,
+{
+    mul_widened(m1, m2, Schedule::Dynamic(thr, chunk))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::create_matrix_from_random;
+    use crate::testing::assert_threads_match;
+
+    // todas as versoes com threads precisam dar o resultado da sequencial
+    // as matrizes sao pequenas e nao quadradas, para ter divisoes desiguais entre as threads
+    #[test]
+    fn threads_match_sequential() -> Result<(), MatrixError> {
+        let m1 = create_matrix_from_random(7, 5, 10);
+        let m2 = create_matrix_from_random(7, 5, 10);
+        let m3 = create_matrix_from_random(5, 3, 10);
+
+        let sum = sum_matrix_sequential(&m1, &m2);
+        assert_threads_match(sum.clone(), |thr| sum_matrix_threads(&m1, &m2, thr));
+        assert_threads_match(sum, |thr| sum_matrix_dynamic(&m1, &m2, thr, DEFAULT_CHUNK));
+        let sub = sub_matrix_sequential(&m1, &m2);
+        assert_threads_match(sub.clone(), |thr| sub_matrix_threads(&m1, &m2, thr));
+        assert_threads_match(sub, |thr| sub_matrix_dynamic(&m1, &m2, thr, DEFAULT_CHUNK));
+
+        let mul = mul_matrix_sequential(&m1, &m3);
+        assert_threads_match(mul.clone(), |thr| mul_matrix_threads(&m1, &m3, thr));
+        assert_threads_match(mul.clone(), |thr| mul_matrix_dynamic(&m1, &m3, thr, 1));
+        assert_threads_match(mul.clone(), |thr| mul_matrix_blocked_threads(&m1, &m3, 2, thr));
+        assert_eq!(mul_matrix_blocked_sequential(&m1, &m3, 2), mul);
+        for partition in [Partition::Rows, Partition::Tiles { tile: 2, k_splits: 1 }, Partition::Tiles { tile: 2, k_splits: 2 }, Partition::Tiles { tile: 4, k_splits: 9 }] {
+            assert_threads_match(mul.clone(), |thr| mul_matrix_partitioned(&m1, &m3, thr, partition));
+        }
+        Ok(())
+    }
+
+    // formas que nao combinam e matrizes vazias sao erro em todas as versoes
+    #[test]
+    fn shape_errors_and_empty_matrices() {
+        let m1 = create_matrix_from_random(3, 4, 10);
+        let m2 = create_matrix_from_random(4, 3, 10);
+        let sum = Err(MatrixError::ShapeMismatch { left: (3, 4), right: (4, 3), op: "sum" });
+        assert_threads_match(sum, |thr| sum_matrix_threads(&m1, &m2, thr));
+        assert!(matches!(mul_matrix_sequential(&m1, &m1), Err(MatrixError::ShapeMismatch { .. })));
+        assert!(matches!(mul_matrix_partitioned(&m1, &m1, 2, Partition::Tiles { tile: 2, k_splits: 2 }), Err(MatrixError::ShapeMismatch { .. })));
+
+        let empty: Matrix<i32> = Matrix::new(0, 4);
+        assert_threads_match(Err(MatrixError::Empty), |thr| sum_matrix_dynamic(&empty, &empty, thr, DEFAULT_CHUNK));
+        assert_threads_match(Err(MatrixError::Empty), |thr| mul_matrix_threads(&empty, &m2, thr));
+        let tiles = Partition::Tiles { tile: 2, k_splits: 2 };
+        assert_threads_match(Err(MatrixError::Empty), |thr| mul_matrix_partitioned(&m1, &Matrix::new(4, 0), thr, tiles));
+    }
+
+    // versoes *_with de todos os kernels com cada modo, que precisam dar o resultado da sequencial
+    fn assert_kernels_match<T: Integer>(m1: &Matrix<T>, m2: &Matrix<T>, m3: &Matrix<T>, mode: Overflow) {
+        let sum = sum_matrix_sequential_with(m1, m2, mode);
+        assert_threads_match(sum.clone(), |thr| sum_matrix_threads_with(m1, m2, thr, mode));
+        assert_threads_match(sum, |thr| sum_matrix_dynamic_with(m1, m2, thr, 1, mode));
+        let sub = sub_matrix_sequential_with(m1, m2, mode);
+        assert_threads_match(sub.clone(), |thr| sub_matrix_threads_with(m1, m2, thr, mode));
+        assert_threads_match(sub, |thr| sub_matrix_dynamic_with(m1, m2, thr, 1, mode));
+        let mul = mul_matrix_sequential_with(m1, m3, mode);
+        assert_eq!(mul_matrix_blocked_sequential_with(m1, m3, 2, mode), mul);
+        assert_threads_match(mul.clone(), |thr| mul_matrix_threads_with(m1, m3, thr, mode));
+        assert_threads_match(mul.clone(), |thr| mul_matrix_dynamic_with(m1, m3, thr, 1, mode));
+        assert_threads_match(mul, |thr| mul_matrix_blocked_threads_with(m1, m3, 2, thr, mode));
+    }
+
+    const MODES: [Overflow; 3] = [Overflow::Checked, Overflow::Wrapping, Overflow::Saturating];
+
+    // com valores pequenos nenhum modo estoura, entao todos dao o mesmo resultado das funcoes normais
+    #[test]
+    fn overflow_modes_without_overflow() -> Result<(), MatrixError> {
+        let m1 = create_matrix_from_random(6, 4, 10);
+        let m2 = create_matrix_from_random(6, 4, 10);
+        let m3 = create_matrix_from_random(4, 5, 10);
+        for mode in MODES {
+            assert_eq!(sum_matrix_sequential(&m1, &m2)?, sum_matrix_sequential_with(&m1, &m2, mode)?);
+            assert_eq!(sub_matrix_sequential(&m1, &m2)?, sub_matrix_sequential_with(&m1, &m2, mode)?);
+            assert_eq!(mul_matrix_sequential(&m1, &m3)?, mul_matrix_sequential_with(&m1, &m3, mode)?);
+            assert_kernels_match(&m1, &m2, &m3, mode);
+        }
+        Ok(())
+    }
+
+    // valores perto do limite: a soma estoura no elemento (1, 0) e o produto no elemento (1, 1)
+    #[test]
+    fn overflow_modes_near_the_limit() -> Result<(), MatrixError> {
+        let big = Matrix::try_from(vec![vec![1, 2], vec![i32::MAX, 3]])?;
+        let ones = Matrix::try_from(vec![vec![1, 1], vec![1, 1]])?;
+        // no produto por [[-1, 1], [1, 1]] so o elemento (1, 1) = i32::MAX + 3 estoura
+        let signs = Matrix::try_from(vec![vec![-1, 1], vec![1, 1]])?;
+        for mode in MODES {
+            assert_kernels_match(&big, &ones, &signs, mode);
+        }
+        let overflow = |row, col, op| Err(MatrixError::Overflow { row, col, op });
+
+        assert_eq!(sum_matrix_sequential_with(&big, &ones, Overflow::Checked), overflow(1, 0, "sum"));
+        assert_eq!(sum_matrix_sequential_with(&big, &ones, Overflow::Wrapping)?[(1, 0)], i32::MIN);
+        assert_eq!(sum_matrix_sequential_with(&big, &ones, Overflow::Saturating)?[(1, 0)], i32::MAX);
+        assert_eq!(mul_matrix_sequential_with(&big, &signs, Overflow::Checked), overflow(1, 1, "mul"));
+        assert_eq!(mul_matrix_sequential_with(&big, &signs, Overflow::Wrapping)?[(1, 1)], i32::MAX.wrapping_add(3));
+        assert_eq!(mul_matrix_sequential_with(&big, &signs, Overflow::Saturating)?[(1, 1)], i32::MAX);
+
+        // uma soma parcial estoura mesmo com o resultado final cabendo em i32; no tipo maior nada estoura
+        let row = Matrix::try_from(vec![vec![i32::MAX, 1, -1]])?;
+        let col = Matrix::try_from(vec![vec![1], vec![1], vec![1]])?;
+        assert_eq!(mul_matrix_sequential_with(&row, &col, Overflow::Checked), overflow(0, 0, "mul"));
+        assert_eq!(mul_matrix_blocked_threads_with(&row, &col, 1, 2, Overflow::Checked), overflow(0, 0, "mul"));
+        let wide = mul_matrix_threads_with(&widen(&row), &widen(&col), 2, Overflow::Checked)?;
+        assert_eq!(wide[(0, 0)], i64::from(i32::MAX));
+        Ok(())
+    }
+
+    // os modos funcionam com outros inteiros, inclusive sem sinal
+    #[test]
+    fn overflow_modes_other_integers() -> Result<(), MatrixError> {
+        let a: Matrix<u8> = Matrix::try_from(vec![vec![200, 1], vec![3, 4]])?;
+        let b: Matrix<u8> = Matrix::try_from(vec![vec![100, 2], vec![5, 6]])?;
+        for mode in MODES {
+            assert_kernels_match(&a, &b, &b, mode);
+        }
+        assert_eq!(sum_matrix_sequential_with(&a, &b, Overflow::Checked), Err(MatrixError::Overflow { row: 0, col: 0, op: "sum" }));
+        assert_eq!(sub_matrix_sequential_with(&b, &a, Overflow::Saturating)?.to_rows(), vec![vec![0, 1], vec![2, 2]]);
+        assert_eq!(mul_matrix_sequential_with(&a, &b, Overflow::Wrapping)?[(0, 0)], (200u8).wrapping_mul(100).wrapping_add(5));
+        assert_eq!(mul_matrix_sequential(&widen(&a), &widen(&b))?[(0, 0)], 20005u16);
+
+        let c: Matrix<i8> = Matrix::try_from(vec![vec![-100, 100]])?;
+        assert_eq!(sub_matrix_threads_with(&c, &c.map(|&x| -x), 2, Overflow::Saturating)?.to_rows(), vec![vec![-128, 127]]);
+        Ok(())
+    }
+
+    // as versoes *_widened dao o resultado exato no tipo maior, igual a converter as entradas com widen antes
+    #[test]
+    fn widened_kernels() -> Result<(), MatrixError> {
+        let big = Matrix::try_from(vec![vec![i32::MAX, i32::MIN, 7], vec![i32::MIN, -1, i32::MAX]])?;
+        let maxes = Matrix::filled(2, 3, i32::MAX);
+        let col = Matrix::try_from(vec![vec![i32::MAX], vec![1], vec![-1]])?;
+
+        let sum: Matrix<i64> = sum_matrix_sequential_widened(&big, &maxes)?;
+        assert_eq!(sum, sum_matrix_sequential(&widen(&big), &widen(&maxes))?);
+        assert_eq!(sum[(0, 0)], 2 * i64::from(i32::MAX));
+        assert_threads_match(Ok(sum.clone()), |thr| sum_matrix_threads_widened(&big, &maxes, thr));
+        assert_threads_match(Ok(sum), |thr| sum_matrix_dynamic_widened(&big, &maxes, thr, 1));
+
+        let sub = sub_matrix_sequential_widened(&big, &maxes)?;
+        assert_eq!(sub, sub_matrix_sequential(&widen(&big), &widen(&maxes))?);
+        assert_threads_match(Ok(sub.clone()), |thr| sub_matrix_threads_widened(&big, &maxes, thr));
+        assert_threads_match(Ok(sub), |thr| sub_matrix_dynamic_widened(&big, &maxes, thr, 1));
+
+        let mul = mul_matrix_sequential_widened(&big, &col)?;
+        assert_eq!(mul, mul_matrix_sequential(&widen(&big), &widen(&col))?);
+        assert_threads_match(Ok(mul.clone()), |thr| mul_matrix_threads_widened(&big, &col, thr));
+        assert_threads_match(Ok(mul), |thr| mul_matrix_dynamic_widened(&big, &col, thr, 1));
+
+        // cada produto cabe no tipo maior, mas (-2^31)^2 + (-2^31)^2 = 2^63 nao
+        let mins = Matrix::filled(1, 2, i32::MIN);
+        let overflow = Err(MatrixError::Overflow { row: 0, col: 0, op: "mul" });
+        assert_eq!(mul_matrix_sequential_widened(&mins, &mins.transpose()), overflow);
+        assert_threads_match(overflow, |thr| mul_matrix_threads_widened(&mins, &mins.transpose(), thr));
+
+        // sem sinal, a subtracao negativa continua sendo um erro, e os tamanhos sao validados como nas outras versoes
+        let a: Matrix<u8> = Matrix::try_from(vec![vec![200, 1]])?;
+        assert_eq!(sum_matrix_sequential_widened(&a, &a)?.to_rows(), vec![vec![400u16, 2]]);
+        assert_eq!(mul_matrix_sequential_widened(&a, &a.transpose())?[(0, 0)], 40001u16);
+        assert_eq!(sub_matrix_sequential_widened(&a, &Matrix::filled(1, 2, 2)), Err(MatrixError::Overflow { row: 0, col: 1, op: "sub" }));
+        assert!(matches!(mul_matrix_threads_widened(&a, &a, 2), Err(MatrixError::ShapeMismatch { .. })));
+        Ok(())
+    }
+}
@@ -0,0 +1,202 @@
+use std::marker::PhantomData;
+use std::mem;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Condvar, Mutex, OnceLock};
+use std::thread::{self, JoinHandle};
+
+// valor especial para a quantidade de threads: usa a quantidade de nucleos da maquina
+pub const AUTO: usize = 0;
+
+// trabalho enviado para as threads do pool
+type Job = Box<dyn FnOnce() + Send + 'static>;
+
+// pool global, compartilhado pelas operacoes de matriz e pelo jogo da vida
+static GLOBAL: OnceLock<ThreadPool> = OnceLock::new();
+
+// conjunto de threads criadas uma unica vez e reaproveitadas em todas as operacoes,
+// assim nao pagamos o custo de thread::spawn a cada chamada
+pub struct ThreadPool {
+    sender: Option<Sender<Job>>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl ThreadPool {
+    // cria o pool com size threads, que ficam esperando trabalho ate o pool ser destruido
+    // size igual a AUTO usa a quantidade de nucleos da maquina
+    pub fn new(size: usize) -> ThreadPool {
+        let size = if size == AUTO { default_size() } else { size };
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+
+        let mut workers = Vec::with_capacity(size);
+        for i in 0..size {
+            let receiver = Arc::clone(&receiver);
+            let handle = thread::Builder::new()
+                .name(format!("pool-worker-{}", i))
+                .spawn(move || worker_loop(receiver))
+                .expect("failed to spawn pool worker");
+            workers.push(handle);
+        }
+        ThreadPool { sender: Some(sender), workers }
+    }
+
+    // quantidade de threads do pool
+    pub fn size(&self) -> usize {
+        self.workers.len()
+    }
+
+    // fork-join: tudo que for criado com s.spawn dentro de f roda nas threads do pool,
+    // e scope so retorna depois que todos os trabalhos terminarem
+    // por isso os trabalhos podem pegar emprestado dados que vivem fora do scope, igual a thread::scope
+    // nao deve ser chamado de dentro de um trabalho do proprio pool, pois a thread ficaria esperando ela mesma
+    pub fn scope<'env, F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(&Scope<'_, 'env>) -> R,
+    {
+        let scope = Scope {
+            pool: self,
+            state: Arc::new(ScopeState {
+                pending: Mutex::new(0),
+                done: Condvar::new(),
+                panicked: AtomicBool::new(false),
+            }),
+            _env: PhantomData,
+        };
+        // mesmo que f entre em panic, precisamos esperar os trabalhos ja enviados,
+        // senao eles continuariam usando dados emprestados que seriam liberados
+        let result = panic::catch_unwind(AssertUnwindSafe(|| f(&scope)));
+        scope.wait();
+
+        match result {
+            Err(payload) => panic::resume_unwind(payload),
+            Ok(_) if scope.state.panicked.load(Ordering::SeqCst) => panic!("a job in the thread pool panicked"),
+            Ok(value) => value,
+        }
+    }
+}
+
+// ao destruir o pool, fecha o canal e espera as threads terminarem
+impl Drop for ThreadPool {
+    fn drop(&mut self) {
+        drop(self.sender.take());
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+// cada thread do pool fica pegando trabalhos do canal ate ele ser fechado
+fn worker_loop(receiver: Arc<Mutex<Receiver<Job>>>) {
+    loop {
+        let job = receiver.lock().unwrap().recv();
+        match job {
+            Ok(job) => job(),
+            Err(_) => break,
+        }
+    }
+}
+
+// contador de trabalhos pendentes de um scope
+struct ScopeState {
+    pending: Mutex<usize>,
+    done: Condvar,
+    panicked: AtomicBool,
+}
+
+// usado dentro de ThreadPool::scope para enviar trabalhos ao pool
+// 'env e o tempo de vida dos dados que os trabalhos podem pegar emprestado
+pub struct Scope<'pool, 'env> {
+    pool: &'pool ThreadPool,
+    state: Arc<ScopeState>,
+    // invariante em 'env, como em std::thread::Scope
+    _env: PhantomData<&'env mut &'env ()>,
+}
+
+impl<'pool, 'env> Scope<'pool, 'env> {
+    // envia f para ser executada em alguma thread do pool
+    pub fn spawn<F>(&self, f: F)
+    where
+        F: FnOnce() + Send + 'env,
+    {
+        *self.state.pending.lock().unwrap() += 1;
+        let state = Arc::clone(&self.state);
+        let job: Box<dyn FnOnce() + Send + 'env> = Box::new(move || {
+            if panic::catch_unwind(AssertUnwindSafe(f)).is_err() {
+                state.panicked.store(true, Ordering::SeqCst);
+            }
+            let mut pending = state.pending.lock().unwrap();
+            *pending -= 1;
+            if *pending == 0 {
+                state.done.notify_all();
+            }
+        });
+        // SAFETY: ThreadPool::scope so retorna depois que pending chega a zero, ou seja,
+        // depois que este trabalho terminou, entao nada emprestado por 'env e usado depois de liberado
+        let job: Job = unsafe { mem::transmute::<Box<dyn FnOnce() + Send + 'env>, Job>(job) };
+        self.pool.sender.as_ref().unwrap().send(job).expect("thread pool is shut down");
+    }
+
+    // espera todos os trabalhos enviados por este scope terminarem
+    fn wait(&self) {
+        let mut pending = self.state.pending.lock().unwrap();
+        while *pending > 0 {
+            pending = self.state.done.wait(pending).unwrap();
+        }
+    }
+}
+
+// tamanho padrao do pool, a quantidade de nucleos da maquina
+pub fn default_size() -> usize {
+    thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+// quantidade de threads que realmente vale a pena usar para dividir work partes de trabalho
+// AUTO vira a quantidade de nucleos, e nunca usamos mais threads do que partes (nem menos que 1),
+// assim nenhuma thread fica com um pedaco vazio
+pub fn effective_threads(requested: usize, work: usize) -> usize {
+    resolve_threads(requested).min(work).max(1)
+}
+
+// quantidade de threads que de fato rodam ao mesmo tempo: effective_threads limitado pelo tamanho do pool global,
+// ja que cada parte do trabalho vira uma tarefa do pool e nao uma thread propria
+pub fn running_threads(requested: usize, work: usize) -> usize {
+    effective_threads(requested, work).min(global().size())
+}
+
+// quantidade de threads pedida, com AUTO trocado pela quantidade de nucleos
+pub fn resolve_threads(requested: usize) -> usize {
+    if requested == AUTO { default_size() } else { requested }
+}
+
+// cria o pool global com size threads, deve ser chamado antes do primeiro uso de global()
+// retorna false se o pool ja tinha sido criado, nesse caso o tamanho antigo e mantido
+pub fn init(size: usize) -> bool {
+    let mut created = false;
+    GLOBAL.get_or_init(|| {
+        created = true;
+        ThreadPool::new(size)
+    });
+    created
+}
+
+// retorna o pool global, criando ele com default_size() caso init nao tenha sido chamado
+pub fn global() -> &'static ThreadPool {
+    GLOBAL.get_or_init(|| ThreadPool::new(default_size()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a quantidade de threads relatada nunca passa do trabalho nem do pool global
+    #[test]
+    fn running_threads_is_capped() {
+        let size = global().size();
+        assert_eq!(running_threads(size + 8, 1000), size);
+        assert_eq!(running_threads(AUTO, 1000), default_size().min(size));
+        assert_eq!(running_threads(4, 2), 2.min(size));
+        assert_eq!(running_threads(4, 0), 1);
+    }
+}
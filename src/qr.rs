@@ -0,0 +1,246 @@
+use crate::lu::PIVOT_TOLERANCE;
+use crate::matrix::{Matrix, MatrixError};
+use crate::ops::split_rows_mut;
+use crate::pool;
+
+// decomposicao QR por reflexoes de Householder: A = Q * R, com A m x n e m >= n
+// Q (m x n) tem colunas ortonormais e R (n x n) e triangular superior
+#[derive(Debug, Clone, PartialEq)]
+pub struct Qr {
+    // transposta da matriz de trabalho, a linha j e a coluna j de A depois das reflexoes
+    // assim cada coluna e um pedaco continuo e as colunas podem ser divididas entre as threads
+    // R fica da diagonal para cima, o resto e zero
+    columns: Matrix<f64>,
+    // vetor unitario de cada reflexao H_k = I - 2 * v * v^T, aplicada nas linhas k em diante
+    // fica vazio quando a coluna ja era toda zero e nao precisou de reflexao
+    reflectors: Vec<Vec<f64>>,
+    // maior elemento de A, usado para decidir se um elemento da diagonal de R e zero
+    scale: f64,
+}
+
+impl Qr {
+    // linhas de A
+    pub fn rows(&self) -> usize {
+        self.columns.cols()
+    }
+
+    // colunas de A
+    pub fn cols(&self) -> usize {
+        self.columns.rows()
+    }
+
+    // matriz triangular superior n x n
+    pub fn r(&self) -> Matrix<f64> {
+        let n = self.cols();
+        let mut r = Matrix::new(n, n);
+        for j in 0..n {
+            for i in 0..=j {
+                r[(i, j)] = self.columns[(j, i)];
+            }
+        }
+        r
+    }
+
+    // matriz m x n com colunas ortonormais, aplicando as reflexoes nas primeiras n colunas da identidade
+    // Q = H_0 * H_1 * ... * H_(n-1) * I, entao as reflexoes sao aplicadas da ultima para a primeira
+    pub fn q(&self) -> Matrix<f64> {
+        let (m, n) = (self.rows(), self.cols());
+        let mut transposed = Matrix::new(n, m);
+        for j in 0..n {
+            transposed[(j, j)] = 1.0;
+        }
+        for (k, v) in self.reflectors.iter().enumerate().rev() {
+            for column in transposed.as_mut_slice().chunks_mut(m) {
+                reflect(column, k, v);
+            }
+        }
+        transposed.transpose()
+    }
+
+    // Q^T * b, aplicando as reflexoes em ordem
+    pub fn qt_mul(&self, b: &[f64]) -> Result<Vec<f64>, MatrixError> {
+        let m = self.rows();
+        if b.len() != m {
+            return Err(MatrixError::Length { expected: m, found: b.len() });
+        }
+        let mut y = b.to_vec();
+        for (k, v) in self.reflectors.iter().enumerate() {
+            reflect(&mut y, k, v);
+        }
+        Ok(y)
+    }
+
+    // x que minimiza |A * x - b|: como Q preserva distancias, basta resolver R * x = (Q^T * b)[..n]
+    // se algum elemento da diagonal de R e zero as colunas de A sao dependentes e x nao e unico
+    pub fn least_squares(&self, b: &[f64]) -> Result<Vec<f64>, MatrixError> {
+        let n = self.cols();
+        let mut x = self.qt_mul(b)?;
+        x.truncate(n);
+        for i in (0..n).rev() {
+            let rii = self.columns[(i, i)];
+            if rii.abs() <= PIVOT_TOLERANCE * self.scale {
+                return Err(MatrixError::Singular { pivot: i });
+            }
+            let sum: f64 = (i + 1..n).map(|j| self.columns[(j, i)] * x[j]).sum();
+            x[i] = (x[i] - sum) / rii;
+        }
+        Ok(x)
+    }
+}
+
+// aplica H_k = I - 2 * v * v^T na coluna, do elemento k para baixo
+fn reflect(column: &mut [f64], k: usize, v: &[f64]) {
+    let tail = &mut column[k..];
+    let dot: f64 = v.iter().zip(tail.iter()).map(|(a, b)| a * b).sum();
+    for (x, &vi) in tail.iter_mut().zip(v) {
+        *x -= 2.0 * dot * vi;
+    }
+}
+
+// reflexao que leva a coluna x no multiplo de e_0 com o mesmo tamanho: H * x = alpha * e_0
+// alpha tem o sinal contrario de x[0], para que v = x - alpha * e_0 nao sofra cancelamento
+// retorna alpha e o vetor v ja normalizado, vazio se x e todo zero
+fn householder(x: &[f64]) -> (f64, Vec<f64>) {
+    let norm = x.iter().map(|v| v * v).sum::<f64>().sqrt();
+    if norm == 0.0 {
+        return (0.0, Vec::new());
+    }
+    let alpha = if x[0] > 0.0 { -norm } else { norm };
+    let mut v = x.to_vec();
+    v[0] -= alpha;
+    let length = v.iter().map(|v| v * v).sum::<f64>().sqrt();
+    for e in v.iter_mut() {
+        *e /= length;
+    }
+    (alpha, v)
+}
+
+// verifica se a matriz tem pelo menos tantas linhas quanto colunas
+fn check_tall(a: &Matrix<f64>, op: &'static str) -> Result<(), MatrixError> {
+    if a.is_empty() {
+        return Err(MatrixError::Empty);
+    }
+    if a.rows() < a.cols() {
+        return Err(MatrixError::Wide { rows: a.rows(), cols: a.cols(), op });
+    }
+    Ok(())
+}
+
+// uma reflexao por coluna: a coluna k vira (alpha, 0, ..., 0) e a reflexao e aplicada nas colunas seguintes
+// thr None aplica nas colunas seguintes sequencialmente, Some(thr) divide essas colunas em blocos entre as threads
+// cada coluna recebe as mesmas contas nas duas versoes, entao o resultado e identico
+fn factor(a: &Matrix<f64>, thr: Option<usize>) -> Qr {
+    let (m, n) = a.shape();
+    let mut columns = a.transpose();
+    let mut reflectors = Vec::with_capacity(n);
+    let scale = a.as_slice().iter().fold(0.0, |s: f64, x| s.max(x.abs()));
+
+    for k in 0..n {
+        let (alpha, v) = householder(&columns.row(k)[k..]);
+        let column = columns.row_mut(k);
+        if !v.is_empty() {
+            column[k] = alpha;
+            column[k + 1..].iter_mut().for_each(|x| *x = 0.0);
+        }
+
+        let trailing = &mut columns.as_mut_slice()[(k + 1) * m..];
+        let v = &v;
+        match thr {
+            None => trailing.chunks_mut(m).for_each(|column| reflect(column, k, v)),
+            Some(thr) => pool::global().scope(|s| {
+                for (_, block) in split_rows_mut(trailing, n - k - 1, m, thr) {
+                    s.spawn(move || block.chunks_mut(m).for_each(|column| reflect(column, k, v)));
+                }
+            }),
+        }
+        reflectors.push(v.clone());
+    }
+    Qr { columns, reflectors, scale }
+}
+
+// decomposicao QR sequencial
+pub fn qr_sequential(a: &Matrix<f64>) -> Result<Qr, MatrixError> {
+    check_tall(a, "qr")?;
+    Ok(factor(a, None))
+}
+
+// decomposicao QR usando threads: cada reflexao e aplicada nas colunas seguintes divididas em blocos,
+// um bloco por thread, pois cada coluna so depende dela mesma e do vetor da reflexao
+pub fn qr_threads(a: &Matrix<f64>, thr: usize) -> Result<Qr, MatrixError> {
+    check_tall(a, "qr")?;
+    Ok(factor(a, Some(thr)))
+}
+
+// minimos quadrados: x que minimiza |a * x - b|, para a com mais linhas do que colunas
+// com a quadrada e inversivel e a solucao exata de a * x = b
+pub fn least_squares(a: &Matrix<f64>, b: &[f64], thr: usize) -> Result<Vec<f64>, MatrixError> {
+    qr_threads(a, thr)?.least_squares(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lu::solve;
+    use crate::numeric::approx_eq;
+    use crate::ops::{mul_matrix_sequential, sub_matrix_sequential};
+    use crate::random::create_f64_matrix_from_random;
+    use crate::semiring::{identity, Standard};
+    use crate::testing::{assert_threads_match, LINALG_TOLERANCE};
+
+    // Q com colunas ortonormais, R triangular superior e Q * R = A
+    #[test]
+    fn factors() -> Result<(), MatrixError> {
+        let (m, n) = (60, 20);
+        let a = create_f64_matrix_from_random(m, n, -10, 10);
+        let qr = qr_sequential(&a)?;
+        assert_threads_match(Ok(qr.clone()), |thr| qr_threads(&a, thr));
+
+        let (q, r) = (qr.q(), qr.r());
+        assert_eq!((q.shape(), r.shape()), ((m, n), (n, n)));
+        assert!((0..n).all(|i| (0..i).all(|j| r[(i, j)] == 0.0)));
+        assert!(approx_eq(&mul_matrix_sequential(&q.transpose(), &q)?, &identity::<Standard<f64>>(n), LINALG_TOLERANCE));
+        assert!(approx_eq(&mul_matrix_sequential(&q, &r)?, &a, LINALG_TOLERANCE));
+        Ok(())
+    }
+
+    #[test]
+    fn least_squares_solutions() -> Result<(), MatrixError> {
+        let (m, n) = (60, 20);
+        let a = create_f64_matrix_from_random(m, n, -10, 10);
+
+        // com b = a * x o residuo minimo e zero, entao a solucao e o proprio x
+        let expected = create_f64_matrix_from_random(n, 1, -10, 10);
+        let b = mul_matrix_sequential(&a, &expected)?;
+        let x = Matrix::from_vec(n, 1, least_squares(&a, b.as_slice(), 3)?)?;
+        assert!(approx_eq(&x, &expected, LINALG_TOLERANCE));
+
+        // com b qualquer, o residuo b - a * x fica ortogonal as colunas de a
+        let b = create_f64_matrix_from_random(m, 1, -10, 10);
+        let x = Matrix::from_vec(n, 1, qr_sequential(&a)?.least_squares(b.as_slice())?)?;
+        let residual = sub_matrix_sequential(&b, &mul_matrix_sequential(&a, &x)?)?;
+        assert!(approx_eq(&mul_matrix_sequential(&a.transpose(), &residual)?, &Matrix::new(n, 1), LINALG_TOLERANCE));
+
+        // com a quadrada, a mesma solucao da LU
+        let square = create_f64_matrix_from_random(n, n, -10, 10);
+        let b = create_f64_matrix_from_random(n, 1, -10, 10);
+        let x_qr = Matrix::from_vec(n, 1, least_squares(&square, b.as_slice(), 2)?)?;
+        let x_lu = Matrix::from_vec(n, 1, solve(&square, b.as_slice(), 2)?)?;
+        assert!(approx_eq(&x_qr, &x_lu, LINALG_TOLERANCE));
+        Ok(())
+    }
+
+    // a coluna 3 e o dobro da coluna 1, entao a solucao nao e unica
+    #[test]
+    fn rank_deficient_and_shape_errors() -> Result<(), MatrixError> {
+        let (m, n) = (60, 20);
+        let a = create_f64_matrix_from_random(m, n, -10, 10);
+        let mut dependent = a.clone();
+        for i in 0..m {
+            dependent[(i, 3)] = 2.0 * a[(i, 1)];
+        }
+        assert!(matches!(least_squares(&dependent, &vec![1.0; m], 3), Err(MatrixError::Singular { .. })));
+        assert!(matches!(qr_sequential(&a.transpose()), Err(MatrixError::Wide { .. })));
+        assert_eq!(qr_sequential(&a)?.least_squares(&[1.0; 3]), Err(MatrixError::Length { expected: m, found: 3 }));
+        Ok(())
+    }
+}
@@ -0,0 +1,146 @@
+use std::cell::RefCell;
+
+use rand::{Rng, SeedableRng, StdRng};
+
+use crate::matrix::Matrix;
+use crate::ops::mul_matrix_sequential;
+use crate::sparse::CsrMatrix;
+
+// semente usada ate reseed ser chamada
+const DEFAULT_SEED: u64 = 0;
+
+thread_local! {
+    // gerador compartilhado pelas funcoes que criam matrizes aleatorias
+    // como as matrizes sao geradas sempre na mesma ordem, a mesma semente gera as mesmas matrizes
+    static RNG: RefCell<StdRng> = RefCell::new(seeded_rng(DEFAULT_SEED));
+}
+
+// gerador deterministico: a mesma semente gera sempre a mesma sequencia de numeros
+pub fn seeded_rng(seed: u64) -> StdRng {
+    StdRng::from_seed(&[(seed & 0xffff_ffff) as usize, (seed >> 32) as usize][..])
+}
+
+// semente nova a cada execucao, para quando nenhuma for passada na linha de comando
+// ela e mostrada no relatorio, entao a execucao pode ser repetida com --seed
+pub fn random_seed() -> u64 {
+    rand::thread_rng().gen()
+}
+
+// reinicia o gerador compartilhado com a semente seed
+pub fn reseed(seed: u64) {
+    RNG.with(|rng| *rng.borrow_mut() = seeded_rng(seed));
+}
+
+// da acesso ao gerador compartilhado
+pub fn with_rng<R>(f: impl FnOnce(&mut StdRng) -> R) -> R {
+    RNG.with(|rng| f(&mut rng.borrow_mut()))
+}
+
+// cria uma matrix com numeros aleatorios, de tamanho maxi de altura e maxj de largura
+// valor pode variar de 0 ate threshold
+pub fn create_matrix_from_random(maxi: usize, maxj: usize, threshold: i32) -> Matrix<i32> {
+    create_matrix_in_range(maxi, maxj, 0, threshold)
+}
+
+// igual a create_matrix_from_random, com os valores indo de min ate max - 1
+pub fn create_matrix_in_range(maxi: usize, maxj: usize, min: i32, max: i32) -> Matrix<i32> {
+    // usa o gerador compartilhado, assim a mesma semente gera as mesmas matrizes
+    with_rng(|rng| {
+        let mut matrix = Matrix::new(maxi, maxj);
+        for i in 0..maxi {
+            for j in 0..maxj {
+                let n: i32 = rng.gen_range(min, max);
+                matrix[(i, j)] = n;
+            }
+        }
+        matrix
+    })
+}
+
+// matriz de f64 com valores inteiros de min ate max - 1, para as decomposicoes
+pub fn create_f64_matrix_from_random(maxi: usize, maxj: usize, min: i32, max: i32) -> Matrix<f64> {
+    create_matrix_in_range(maxi, maxj, min, max).map(|&v| v as f64)
+}
+
+// cria uma matriz n x n simetrica definida positiva: B^T * B + n * I, com B aleatoria de 0 ate threshold - 1
+// B^T * B ja e semidefinida positiva, e somar n na diagonal garante que todos os autovalores ficam positivos
+pub fn create_spd_matrix_from_random(n: usize, threshold: i32) -> Matrix<f64> {
+    let b = create_matrix_from_random(n, n, threshold).map(|&v| v as f64);
+    let mut spd = mul_matrix_sequential(&b.transpose(), &b).unwrap();
+    for i in 0..n {
+        spd[(i, i)] += n as f64;
+    }
+    spd
+}
+
+// cria uma matriz esparsa maxi x maxj onde cada elemento tem probabilidade density de ser diferente de zero
+// os elementos diferentes de zero vao de 1 ate threshold - 1; com threshold <= 1 todos eles sao 1
+// a matriz e gerada linha por linha ja no formato CSR, sem passar por uma matriz densa
+pub fn create_sparse_matrix_from_random(maxi: usize, maxj: usize, density: f32, threshold: i32) -> CsrMatrix<i32> {
+    let threshold = threshold.max(2);
+    with_rng(|rng| {
+        let mut row_ptr = Vec::with_capacity(maxi + 1);
+        row_ptr.push(0);
+        let mut col_idx = Vec::new();
+        let mut values = Vec::new();
+        for _ in 0..maxi {
+            for j in 0..maxj {
+                if rng.gen::<f32>() < density {
+                    col_idx.push(j);
+                    values.push(rng.gen_range(1, threshold));
+                }
+            }
+            row_ptr.push(col_idx.len());
+        }
+        CsrMatrix::from_parts(maxi, maxj, row_ptr, col_idx, values)
+    })
+}
+
+// matriz de bool aleatoria, com cerca de metade das celulas em true
+pub fn create_bool_matrix_from_random(rows: usize, cols: usize) -> Matrix<bool> {
+    create_matrix_from_random(rows, cols, 2).map(|&v| v == 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conway;
+
+    // a mesma semente precisa gerar as mesmas matrizes e o mesmo tabuleiro, e sementes diferentes dados diferentes
+    #[test]
+    fn same_seed_same_data() {
+        let generate = |seed| {
+            reseed(seed);
+            (create_matrix_in_range(20, 30, -50, 50), create_sparse_matrix_from_random(20, 30, 0.2, 10))
+        };
+        assert_eq!(generate(42), generate(42));
+        assert_ne!(generate(42), generate(43));
+
+        let board = |seed| {
+            let mut board = conway::board_new(30, 20);
+            conway::randomize_board(&mut board, 20, 30, 0.4, &mut seeded_rng(seed));
+            board
+        };
+        assert_eq!(board(7), board(7));
+        assert_ne!(board(7), board(8));
+    }
+
+    // densidade 0 nao guarda nenhum elemento e densidade 1 guarda todos, com os valores de 1 ate threshold - 1
+    #[test]
+    fn sparse_density_limits() {
+        let empty = create_sparse_matrix_from_random(12, 7, 0.0, 10);
+        assert_eq!((empty.shape(), empty.nnz()), ((12, 7), 0));
+        assert_eq!(empty.to_dense(), Matrix::new(12, 7));
+
+        let full = create_sparse_matrix_from_random(12, 7, 1.0, 10);
+        assert_eq!(full.nnz(), 12 * 7);
+        assert!(full.to_dense().as_slice().iter().all(|&x| (1..10).contains(&x)));
+        assert_eq!(CsrMatrix::from_dense(&full.to_dense()), full);
+
+        // sem valores entre 1 e threshold - 1, os elementos ficam em 1 em vez de entrar em panic
+        for threshold in [1, 0, -5] {
+            let ones = create_sparse_matrix_from_random(4, 5, 1.0, threshold);
+            assert_eq!(ones.to_dense(), Matrix::filled(4, 5, 1));
+        }
+    }
+}
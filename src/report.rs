@@ -0,0 +1,485 @@
+use std::borrow::Cow;
+use std::env;
+use std::error::Error;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::mem;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::bench::{self, BenchConfig, Stats};
+use crate::formats::FormatError;
+use crate::pool;
+
+// formatos dos relatorios de benchmark
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Csv,
+    Json,
+}
+
+impl ReportFormat {
+    // escolhe o formato pela extensao: .csv ou .json
+    pub fn from_path(path: &Path) -> Option<ReportFormat> {
+        let ext = path.extension().and_then(|e| e.to_str())?.to_lowercase();
+        match ext.as_str() {
+            "csv" => Some(ReportFormat::Csv),
+            "json" => Some(ReportFormat::Json),
+            _ => None,
+        }
+    }
+}
+
+pub(crate) fn report_format(path: &Path) -> Result<ReportFormat, FormatError> {
+    ReportFormat::from_path(path).ok_or_else(|| FormatError::UnknownFormat(path.display().to_string()))
+}
+
+// maquina onde o benchmark rodou, para saber se dois relatorios podem ser comparados
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Host {
+    pub hostname: String,
+    pub os: String,
+    pub arch: String,
+    pub cores: usize,
+    pub pool_threads: usize,
+}
+
+impl Host {
+    pub fn current() -> Host {
+        // sem dependencias extras o nome da maquina vem da variavel HOSTNAME ou de /etc/hostname
+        let hostname = env::var("HOSTNAME")
+            .ok()
+            .or_else(|| fs::read_to_string("/etc/hostname").ok())
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| "unknown".to_string());
+        Host {
+            hostname,
+            os: env::consts::OS.to_string(),
+            arch: env::consts::ARCH.to_string(),
+            cores: pool::default_size(),
+            pool_threads: pool::global().size(),
+        }
+    }
+}
+
+impl fmt::Display for Host {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} ({} {}, {} cores, pool with {} threads)",
+            self.hostname, self.os, self.arch, self.cores, self.pool_threads
+        )
+    }
+}
+
+// uma medicao: a operacao, a estrategia usada, o tamanho da matriz, as threads e os tempos
+// threads e None nas versoes sequenciais, e nas com threads ja vem sem pool::AUTO
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Record {
+    pub operation: String,
+    pub strategy: String,
+    pub rows: usize,
+    pub cols: usize,
+    pub threads: Option<usize>,
+    pub stats: Stats,
+}
+
+impl Record {
+    pub fn new(operation: &str, strategy: &str, shape: (usize, usize), threads: Option<usize>, stats: Stats) -> Record {
+        Record {
+            operation: operation.to_string(),
+            strategy: strategy.to_string(),
+            rows: shape.0,
+            cols: shape.1,
+            threads: threads.map(pool::resolve_threads),
+            stats,
+        }
+    }
+
+    // nome mostrado no relatorio em texto, como "Sum - Threads (4)"
+    pub fn label(&self) -> String {
+        match self.threads {
+            Some(threads) => format!("{} - {} ({})", self.operation, self.strategy, threads),
+            None => format!("{} - {}", self.operation, self.strategy),
+        }
+    }
+
+    // duas medicoes sao do mesmo caso quando so os tempos mudam
+    pub fn same_case(&self, other: &Record) -> bool {
+        self.operation == other.operation
+            && self.strategy == other.strategy
+            && (self.rows, self.cols) == (other.rows, other.cols)
+            && self.threads == other.threads
+    }
+}
+
+// guarda as medicoes feitas durante a execucao, para gravar o relatorio no final
+#[derive(Debug, Default)]
+pub struct Recorder {
+    records: Vec<Record>,
+}
+
+impl Recorder {
+    pub fn new() -> Recorder {
+        Recorder::default()
+    }
+
+    // guarda a medicao e retorna a linha do relatorio em texto,
+    // com o speedup sobre a versao sequencial quando ela e passada
+    pub fn add(&mut self, record: Record, sequential: Option<&Stats>) -> String {
+        let line = bench::report(&record.label(), &record.stats, sequential);
+        self.records.push(record);
+        line
+    }
+
+    pub fn into_records(self) -> Vec<Record> {
+        self.records
+    }
+}
+
+// relatorio completo de uma execucao, gravado em JSON com os mesmos nomes dos campos
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Report {
+    pub seed: u64,
+    pub host: Host,
+    pub config: BenchConfig,
+    pub records: Vec<Record>,
+}
+
+// colunas do CSV, as de host e semente se repetem em todas as linhas para cada linha ser independente
+const CSV_HEADER: &str = "seed,hostname,os,arch,cores,pool_threads,warmup,repetitions,operation,strategy,rows,cols,threads,samples,min_ns,median_ns,mean_ns,stddev_ns";
+
+impl Report {
+    pub fn write_csv(&self, mut writer: impl Write) -> Result<(), FormatError> {
+        writeln!(writer, "{}", CSV_HEADER)?;
+        for record in &self.records {
+            writeln!(
+                writer,
+                "{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
+                self.seed,
+                csv_field(&self.host.hostname),
+                csv_field(&self.host.os),
+                csv_field(&self.host.arch),
+                self.host.cores,
+                self.host.pool_threads,
+                self.config.warmup,
+                self.config.repetitions,
+                csv_field(&record.operation),
+                csv_field(&record.strategy),
+                record.rows,
+                record.cols,
+                record.threads.map_or(String::new(), |t| t.to_string()),
+                record.stats.samples,
+                record.stats.min,
+                record.stats.median,
+                record.stats.mean,
+                record.stats.stddev
+            )?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    pub fn write_json(&self, mut writer: impl Write) -> Result<(), FormatError> {
+        serde_json::to_writer_pretty(&mut writer, self)?;
+        writeln!(writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    // grava em CSV ou JSON, escolhido pela extensao do arquivo
+    pub fn write_file(&self, path: &Path) -> Result<(), FormatError> {
+        let format = report_format(path)?;
+        let writer = BufWriter::new(File::create(path)?);
+        match format {
+            ReportFormat::Csv => self.write_csv(writer),
+            ReportFormat::Json => self.write_json(writer),
+        }
+    }
+}
+
+// medicoes de um relatorio gravado por Report::write_file, usado como referencia,
+// junto com a maquina onde elas foram feitas (None num CSV sem medicoes)
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Baseline {
+    pub host: Option<Host>,
+    pub records: Vec<Record>,
+}
+
+pub fn read_baseline(path: &Path) -> Result<Baseline, FormatError> {
+    let format = report_format(path)?;
+    let reader = BufReader::new(File::open(path)?);
+    match format {
+        ReportFormat::Csv => read_baseline_csv(reader),
+        ReportFormat::Json => read_baseline_json(reader),
+    }
+}
+
+pub fn read_baseline_csv(mut reader: impl Read) -> Result<Baseline, FormatError> {
+    let mut text = String::new();
+    reader.read_to_string(&mut text)?;
+    let mut rows = csv_records(&text)?.into_iter();
+    let columns = match rows.next() {
+        Some((_, columns)) => columns,
+        None => return Err(FormatError::Parse { line: 1, message: "empty file".to_string() }),
+    };
+    let column = |name: &str| {
+        columns
+            .iter()
+            .position(|c| c == name)
+            .ok_or_else(|| FormatError::Parse { line: 1, message: format!("missing column {}", name) })
+    };
+    let idx = [
+        column("operation")?,
+        column("strategy")?,
+        column("rows")?,
+        column("cols")?,
+        column("threads")?,
+        column("samples")?,
+        column("min_ns")?,
+        column("median_ns")?,
+        column("mean_ns")?,
+        column("stddev_ns")?,
+    ];
+    let host_idx = [column("hostname")?, column("os")?, column("arch")?, column("cores")?, column("pool_threads")?];
+
+    let mut host = None;
+    let mut records = Vec::new();
+    for (line_number, fields) in rows {
+        if fields.len() != columns.len() {
+            return Err(FormatError::Parse {
+                line: line_number,
+                message: format!("expected {} fields, found {}", columns.len(), fields.len()),
+            });
+        }
+        let number = |i: usize| {
+            fields[i].parse::<f64>().map_err(|_| FormatError::Parse {
+                line: line_number,
+                message: format!("invalid number {:?} in column {}", fields[i], columns[i]),
+            })
+        };
+        // a maquina se repete em todas as linhas, vale a da primeira
+        if host.is_none() {
+            host = Some(Host {
+                hostname: fields[host_idx[0]].clone(),
+                os: fields[host_idx[1]].clone(),
+                arch: fields[host_idx[2]].clone(),
+                cores: number(host_idx[3])? as usize,
+                pool_threads: number(host_idx[4])? as usize,
+            });
+        }
+        let threads = match fields[idx[4]].as_str() {
+            "" => None,
+            _ => Some(number(idx[4])? as usize),
+        };
+        records.push(Record {
+            operation: fields[idx[0]].clone(),
+            strategy: fields[idx[1]].clone(),
+            rows: number(idx[2])? as usize,
+            cols: number(idx[3])? as usize,
+            threads,
+            stats: Stats {
+                samples: number(idx[5])? as usize,
+                min: number(idx[6])?,
+                median: number(idx[7])?,
+                mean: number(idx[8])?,
+                stddev: number(idx[9])?,
+            },
+        });
+    }
+    Ok(Baseline { host, records })
+}
+
+// os outros campos do relatorio (seed, config) sao ignorados
+pub fn read_baseline_json(reader: impl Read) -> Result<Baseline, FormatError> {
+    Ok(serde_json::from_reader(reader)?)
+}
+
+// uma medicao atual junto com a mesma medicao da referencia, se ela existir la
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change<'a> {
+    pub current: &'a Record,
+    pub baseline: Option<&'a Record>,
+}
+
+impl Change<'_> {
+    // quanto a mediana mudou em relacao a referencia, em porcentagem; positivo e mais lento
+    pub fn percent(&self) -> Option<f64> {
+        let baseline = self.baseline?.stats.median.max(1.0);
+        Some((self.current.stats.median - baseline) / baseline * 100.0)
+    }
+
+    // a medicao ficou mais lenta que a referencia em mais de max_regression por cento
+    pub fn is_regression(&self, max_regression: f64) -> bool {
+        self.percent().is_some_and(|percent| percent > max_regression)
+    }
+}
+
+// resultado da comparacao com a referencia
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comparison<'a> {
+    // cada medicao atual, com a da referencia quando ela existe
+    pub changes: Vec<Change<'a>>,
+    // casos da referencia que nao foram medidos nesta execucao
+    pub missing: Vec<&'a Record>,
+}
+
+// junta cada medicao atual com a mesma medicao da referencia e separa os casos da referencia que sumiram
+pub fn compare<'a>(baseline: &'a [Record], current: &'a [Record]) -> Comparison<'a> {
+    let changes = current
+        .iter()
+        .map(|record| Change { current: record, baseline: baseline.iter().find(|b| b.same_case(record)) })
+        .collect();
+    let missing = baseline.iter().filter(|b| !current.iter().any(|record| record.same_case(b))).collect();
+    Comparison { changes, missing }
+}
+
+// erro quando alguma medicao ficou mais lenta que a referencia alem do limite
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegressionError {
+    pub regressions: usize,
+    pub max_regression: f64,
+}
+
+impl fmt::Display for RegressionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} case(s) regressed by more than {}% against the baseline",
+            self.regressions, self.max_regression
+        )
+    }
+}
+
+impl Error for RegressionError {}
+
+// campo de CSV pela RFC 4180: entre aspas quando tem virgula, aspas ou quebra de linha,
+// com as aspas de dentro dobradas
+pub(crate) fn csv_field(text: &str) -> Cow<'_, str> {
+    if text.contains([',', '"', '\n', '\r']) {
+        Cow::Owned(format!("\"{}\"", text.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+// divide o texto em registros CSV pela RFC 4180, aceitando campos entre aspas com virgulas,
+// quebras de linha e aspas dobradas; cada registro vem com a linha do arquivo onde comeca
+// linhas em branco sao ignoradas
+fn csv_records(text: &str) -> Result<Vec<(usize, Vec<String>)>, FormatError> {
+    let mut records = Vec::new();
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let (mut line, mut start) = (1, 1);
+    let mut quoted = false;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted => {
+                if chars.peek() == Some(&'"') {
+                    chars.next();
+                    field.push('"');
+                } else {
+                    quoted = false;
+                }
+            }
+            '\n' if quoted => {
+                line += 1;
+                field.push(c);
+            }
+            _ if quoted => field.push(c),
+            '"' if field.is_empty() => quoted = true,
+            '"' => {
+                return Err(FormatError::Parse { line, message: "quote inside an unquoted field".to_string() });
+            }
+            ',' => fields.push(mem::take(&mut field)),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                if !fields.is_empty() || !field.is_empty() {
+                    fields.push(mem::take(&mut field));
+                    records.push((start, mem::take(&mut fields)));
+                }
+                line += 1;
+                start = line;
+            }
+            c => field.push(c),
+        }
+    }
+    if quoted {
+        return Err(FormatError::Parse { line: start, message: "unterminated quoted field".to_string() });
+    }
+    if !fields.is_empty() || !field.is_empty() {
+        fields.push(field);
+        records.push((start, fields));
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    // relatorio com texto que precisa de aspas no CSV e de escape no JSON
+    fn sample_report() -> Report {
+        let stats = Stats { samples: 3, min: 10.0, median: 12.5, mean: 13.0, stddev: 1.5 };
+        Report {
+            seed: 42,
+            host: Host {
+                hostname: "lab, \"rack 2\"\nnode".to_string(),
+                os: "linux".to_string(),
+                arch: "x86_64".to_string(),
+                cores: 8,
+                pool_threads: 4,
+            },
+            config: BenchConfig::default(),
+            records: vec![
+                Record::new("Sum", "Sequential", (100, 100), None, stats),
+                Record::new("Mul, blocked", "Threads", (100, 50), Some(4), stats),
+            ],
+        }
+    }
+
+    #[test]
+    fn csv_round_trip_with_quoted_fields() -> Result<(), FormatError> {
+        let report = sample_report();
+        let mut buffer = Vec::new();
+        report.write_csv(&mut buffer)?;
+        let baseline = read_baseline_csv(Cursor::new(buffer))?;
+        assert_eq!(baseline, Baseline { host: Some(report.host), records: report.records });
+
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a,\"b\""), "\"a,\"\"b\"\"\"");
+        let text = format!("{}\n1,\"open\n", CSV_HEADER);
+        assert!(matches!(read_baseline_csv(Cursor::new(text)), Err(FormatError::Parse { line: 2, .. })));
+        Ok(())
+    }
+
+    #[test]
+    fn json_round_trip() -> Result<(), FormatError> {
+        let report = sample_report();
+        let mut buffer = Vec::new();
+        report.write_json(&mut buffer)?;
+        let baseline = read_baseline_json(Cursor::new(buffer))?;
+        assert_eq!(baseline, Baseline { host: Some(report.host), records: report.records });
+        assert!(matches!(read_baseline_json(Cursor::new("{\n\"records\": [1]\n}")), Err(FormatError::Parse { line: 2, .. })));
+        Ok(())
+    }
+
+    // casos novos ficam sem referencia e os casos da referencia que sumiram sao listados
+    #[test]
+    fn compare_reports_new_and_missing_cases() {
+        let baseline = sample_report().records;
+        let mut current = baseline.clone();
+        current[1].stats.median = 25.0;
+        current[0].threads = Some(2);
+        let comparison = compare(&baseline, &current);
+        assert_eq!(comparison.changes[0].baseline, None);
+        assert_eq!(comparison.changes[1].percent(), Some(100.0));
+        assert!(comparison.changes[1].is_regression(50.0));
+        assert_eq!(comparison.missing, vec![&baseline[0]]);
+    }
+}
@@ -0,0 +1,219 @@
+use std::fmt::Debug;
+
+use crate::matrix::{Matrix, MatrixError};
+use crate::numeric::Numeric;
+use crate::ops::{check_mul_shape, split_rows_mut};
+use crate::pool;
+
+// semianel: troca o + e o * da multiplicacao de matrizes por outras operacoes
+// c[i][j] = add(... add(mul(a[i][0], b[0][j]), mul(a[i][1], b[1][j])) ...), comecando de zero()
+// zero() precisa ser neutro de add, e one() neutro de mul
+pub trait Semiring {
+    type Elem: Copy + PartialEq + Debug + Send + Sync + 'static;
+
+    fn zero() -> Self::Elem;
+    fn one() -> Self::Elem;
+    fn add(a: Self::Elem, b: Self::Elem) -> Self::Elem;
+    fn mul(a: Self::Elem, b: Self::Elem) -> Self::Elem;
+}
+
+// o semianel normal (+, *), com ele a multiplicacao e igual a mul_matrix_sequential
+pub struct Standard<T>(std::marker::PhantomData<T>);
+
+impl<T: Numeric> Semiring for Standard<T> {
+    type Elem = T;
+
+    fn zero() -> T {
+        T::default()
+    }
+    fn one() -> T {
+        T::from_i32(1)
+    }
+    fn add(a: T, b: T) -> T {
+        a + b
+    }
+    fn mul(a: T, b: T) -> T {
+        a * b
+    }
+}
+
+// semianel tropical (min, +), para caminhos minimos
+// a[i][j] e o peso da aresta i -> j, infinito quando nao existe aresta e 0 na diagonal
+pub struct MinPlus;
+
+impl Semiring for MinPlus {
+    type Elem = f64;
+
+    fn zero() -> f64 {
+        f64::INFINITY
+    }
+    fn one() -> f64 {
+        0.0
+    }
+    fn add(a: f64, b: f64) -> f64 {
+        a.min(b)
+    }
+    fn mul(a: f64, b: f64) -> f64 {
+        a + b
+    }
+}
+
+// semianel (max, +), para caminhos mais longos em grafos sem ciclo, como o caminho critico de um cronograma
+// menos infinito quando nao existe aresta
+pub struct MaxPlus;
+
+impl Semiring for MaxPlus {
+    type Elem = f64;
+
+    fn zero() -> f64 {
+        f64::NEG_INFINITY
+    }
+    fn one() -> f64 {
+        0.0
+    }
+    fn add(a: f64, b: f64) -> f64 {
+        a.max(b)
+    }
+    fn mul(a: f64, b: f64) -> f64 {
+        a + b
+    }
+}
+
+// semianel booleano (OR, AND), para alcancabilidade
+pub struct Boolean;
+
+impl Semiring for Boolean {
+    type Elem = bool;
+
+    fn zero() -> bool {
+        false
+    }
+    fn one() -> bool {
+        true
+    }
+    fn add(a: bool, b: bool) -> bool {
+        a || b
+    }
+    fn mul(a: bool, b: bool) -> bool {
+        a && b
+    }
+}
+
+// matriz identidade n x n do semianel S: one() na diagonal e zero() no resto
+// para grafos, e a matriz de adjacencia sem nenhuma aresta
+pub fn identity<S: Semiring>(n: usize) -> Matrix<S::Elem> {
+    let mut m = Matrix::filled(n, n, S::zero());
+    for i in 0..n {
+        m[(i, i)] = S::one();
+    }
+    m
+}
+
+// calcula a linha start + r da saida para cada linha r de out
+fn mul_semiring_rows<S: Semiring>(m1: &Matrix<S::Elem>, m2: &Matrix<S::Elem>, start: usize, out: &mut [S::Elem]) {
+    let cols = m2.cols();
+    for (r, row) in out.chunks_mut(cols).enumerate() {
+        let a = m1.row(start + r);
+        for (j, x) in row.iter_mut().enumerate() {
+            let mut acc = S::zero();
+            for (k, &aik) in a.iter().enumerate() {
+                acc = S::add(acc, S::mul(aik, m2[(k, j)]));
+            }
+            *x = acc;
+        }
+    }
+}
+
+// multiplicacao de matrizes sobre o semianel S, por exemplo mul_matrix_semiring_sequential::<MinPlus>(&d, &d)
+pub fn mul_matrix_semiring_sequential<S: Semiring>(m1: &Matrix<S::Elem>, m2: &Matrix<S::Elem>) -> Result<Matrix<S::Elem>, MatrixError> {
+    check_mul_shape(m1, m2, "mul_semiring")?;
+    let mut result = Matrix::filled(m1.rows(), m2.cols(), S::zero());
+    mul_semiring_rows::<S>(m1, m2, 0, result.as_mut_slice());
+    Ok(result)
+}
+
+// multiplicacao sobre o semianel S usando threads, dividindo as linhas igual a mul_matrix_threads
+pub fn mul_matrix_semiring_threads<S: Semiring>(m1: &Matrix<S::Elem>, m2: &Matrix<S::Elem>, thr: usize) -> Result<Matrix<S::Elem>, MatrixError> {
+    check_mul_shape(m1, m2, "mul_semiring")?;
+    let rows = m1.rows();
+    let cols = m2.cols();
+    let mut result = Matrix::filled(rows, cols, S::zero());
+
+    pool::global().scope(|s| {
+        for (start, chunk) in split_rows_mut(result.as_mut_slice(), rows, cols, thr) {
+            s.spawn(move || mul_semiring_rows::<S>(m1, m2, start, chunk));
+        }
+    });
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ops::mul_matrix_sequential;
+    use crate::random::create_matrix_from_random;
+    use crate::testing::{assert_threads_match, semiring_paths};
+
+    // grafo pequeno com respostas conhecidas
+    // arestas: 0 -> 1 (4), 0 -> 2 (1), 2 -> 1 (2), 1 -> 3 (1), 2 -> 3 (5)
+    const EDGES: [(usize, usize, f64); 5] = [(0, 1, 4.0), (0, 2, 1.0), (2, 1, 2.0), (1, 3, 1.0), (2, 3, 5.0)];
+
+    // a identidade do semianel ja tem 0 na diagonal e "sem aresta" no resto
+    fn adjacency<S: Semiring>(weight: impl Fn(f64) -> S::Elem) -> Matrix<S::Elem> {
+        let mut adjacency = identity::<S>(4);
+        for &(i, j, w) in &EDGES {
+            adjacency[(i, j)] = weight(w);
+        }
+        adjacency
+    }
+
+    // (min, +): menor distancia entre cada par de vertices
+    #[test]
+    fn min_plus_shortest_paths() -> Result<(), MatrixError> {
+        let inf = f64::INFINITY;
+        let shortest = semiring_paths::<MinPlus>(&adjacency::<MinPlus>(|w| w))?;
+        assert_eq!(shortest.to_rows(), vec![
+            vec![0.0, 3.0, 1.0, 4.0],
+            vec![inf, 0.0, inf, 1.0],
+            vec![inf, 2.0, 0.0, 3.0],
+            vec![inf, inf, inf, 0.0],
+        ]);
+        Ok(())
+    }
+
+    // (max, +): maior caminho, como o caminho critico se as arestas forem duracoes de tarefas
+    #[test]
+    fn max_plus_longest_paths() -> Result<(), MatrixError> {
+        let ninf = f64::NEG_INFINITY;
+        let longest = semiring_paths::<MaxPlus>(&adjacency::<MaxPlus>(|w| w))?;
+        assert_eq!(longest.to_rows(), vec![
+            vec![0.0, 4.0, 1.0, 6.0],
+            vec![ninf, 0.0, ninf, 1.0],
+            vec![ninf, 2.0, 0.0, 5.0],
+            vec![ninf, ninf, ninf, 0.0],
+        ]);
+        Ok(())
+    }
+
+    // (OR, AND): quais vertices sao alcancaveis a partir de cada vertice
+    #[test]
+    fn boolean_reachability() -> Result<(), MatrixError> {
+        let reachable = semiring_paths::<Boolean>(&adjacency::<Boolean>(|_| true))?;
+        assert_eq!(reachable.to_rows(), vec![
+            vec![true, true, true, true],
+            vec![false, true, false, true],
+            vec![false, true, true, true],
+            vec![false, false, false, true],
+        ]);
+        Ok(())
+    }
+
+    // com o semianel normal o resultado e o mesmo da multiplicacao comum
+    #[test]
+    fn standard_matches_ordinary_mul() {
+        let m1 = create_matrix_from_random(6, 4, 10);
+        let m2 = create_matrix_from_random(4, 5, 10);
+        assert_threads_match(mul_matrix_sequential(&m1, &m2), |thr| mul_matrix_semiring_threads::<Standard<i32>>(&m1, &m2, thr));
+        assert!(matches!(mul_matrix_semiring_sequential::<Standard<i32>>(&m1, &m1), Err(MatrixError::ShapeMismatch { .. })));
+    }
+}
@@ -0,0 +1,413 @@
+use crate::matrix::{Matrix, MatrixError};
+use crate::numeric::Numeric;
+use crate::ops::split_rows_mut;
+use crate::pool;
+
+// matriz esparsa no formato CSR (compressed sparse row): guarda so os elementos diferentes de zero
+// os elementos da linha i ficam em col_idx[row_ptr[i]..row_ptr[i + 1]] (colunas, em ordem crescente)
+// e values[row_ptr[i]..row_ptr[i + 1]] (valores)
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsrMatrix<T> {
+    rows: usize,
+    cols: usize,
+    row_ptr: Vec<usize>,
+    col_idx: Vec<usize>,
+    values: Vec<T>,
+}
+
+// o mesmo formato por colunas, CSC (compressed sparse column)
+// os elementos da coluna j ficam em row_idx[col_ptr[j]..col_ptr[j + 1]] e values[col_ptr[j]..col_ptr[j + 1]]
+#[derive(Debug, Clone, PartialEq)]
+pub struct CscMatrix<T> {
+    rows: usize,
+    cols: usize,
+    col_ptr: Vec<usize>,
+    row_idx: Vec<usize>,
+    values: Vec<T>,
+}
+
+impl<T: Numeric> CsrMatrix<T> {
+    // converte de uma matriz densa, ignorando os zeros
+    pub fn from_dense(m: &Matrix<T>) -> CsrMatrix<T> {
+        let zero = T::default();
+        let mut row_ptr = vec![0];
+        let mut col_idx = Vec::new();
+        let mut values = Vec::new();
+        for row in m.iter_rows() {
+            for (j, &x) in row.iter().enumerate() {
+                if x != zero {
+                    col_idx.push(j);
+                    values.push(x);
+                }
+            }
+            row_ptr.push(col_idx.len());
+        }
+        CsrMatrix { rows: m.rows(), cols: m.cols(), row_ptr, col_idx, values }
+    }
+
+    // monta direto a partir dos vetores do formato, para quem ja gera as linhas em ordem
+    // as colunas de cada linha precisam estar em ordem crescente, sem zeros guardados
+    pub(crate) fn from_parts(rows: usize, cols: usize, row_ptr: Vec<usize>, col_idx: Vec<usize>, values: Vec<T>) -> CsrMatrix<T> {
+        debug_assert_eq!(row_ptr.len(), rows + 1);
+        debug_assert_eq!(row_ptr.last(), Some(&col_idx.len()));
+        debug_assert_eq!(col_idx.len(), values.len());
+        CsrMatrix { rows, cols, row_ptr, col_idx, values }
+    }
+
+    // cria a partir de uma lista de (linha, coluna, valor) em qualquer ordem
+    // valores repetidos na mesma posicao sao somados, e os que ficam zero sao descartados
+    pub fn from_triplets(rows: usize, cols: usize, mut entries: Vec<(usize, usize, T)>) -> Result<CsrMatrix<T>, MatrixError> {
+        if let Some(&(i, j, _)) = entries.iter().find(|&&(i, j, _)| i >= rows || j >= cols) {
+            return Err(MatrixError::IndexOutOfBounds { row: i, col: j, rows, cols });
+        }
+        entries.sort_by_key(|&(i, j, _)| (i, j));
+
+        // junta as posicoes repetidas, que ficam vizinhas depois de ordenar
+        let mut merged: Vec<(usize, usize, T)> = Vec::with_capacity(entries.len());
+        for (i, j, x) in entries {
+            match merged.last_mut() {
+                Some(last) if (last.0, last.1) == (i, j) => last.2 += x,
+                _ => merged.push((i, j, x)),
+            }
+        }
+
+        let zero = T::default();
+        let mut row_ptr = vec![0; rows + 1];
+        let mut col_idx = Vec::with_capacity(merged.len());
+        let mut values = Vec::with_capacity(merged.len());
+        for (i, j, x) in merged.into_iter().filter(|&(_, _, x)| x != zero) {
+            row_ptr[i + 1] += 1;
+            col_idx.push(j);
+            values.push(x);
+        }
+        for i in 0..rows {
+            row_ptr[i + 1] += row_ptr[i];
+        }
+        Ok(CsrMatrix { rows, cols, row_ptr, col_idx, values })
+    }
+
+    // lista de (linha, coluna, valor) dos elementos guardados, em ordem de linhas
+    pub fn triplets(&self) -> Vec<(usize, usize, T)> {
+        let mut entries = Vec::with_capacity(self.nnz());
+        for i in 0..self.rows {
+            let (cols, values) = self.row(i);
+            for (&j, &x) in cols.iter().zip(values) {
+                entries.push((i, j, x));
+            }
+        }
+        entries
+    }
+
+    // converte de volta para uma matriz densa, com zero nas posicoes que nao estao guardadas
+    pub fn to_dense(&self) -> Matrix<T> {
+        let mut m = Matrix::new(self.rows, self.cols);
+        for i in 0..self.rows {
+            let (cols, values) = self.row(i);
+            for (&j, &x) in cols.iter().zip(values) {
+                m[(i, j)] = x;
+            }
+        }
+        m
+    }
+
+    // converte para CSC, contando quantos elementos cada coluna tem e depois distribuindo
+    pub fn to_csc(&self) -> CscMatrix<T> {
+        let mut col_ptr = vec![0; self.cols + 1];
+        for &j in &self.col_idx {
+            col_ptr[j + 1] += 1;
+        }
+        for j in 0..self.cols {
+            col_ptr[j + 1] += col_ptr[j];
+        }
+        let mut next = col_ptr.clone();
+        let mut row_idx = vec![0; self.nnz()];
+        let mut values = vec![T::default(); self.nnz()];
+        // percorrer as linhas em ordem deixa as linhas de cada coluna em ordem crescente
+        for i in 0..self.rows {
+            let (cols, vals) = self.row(i);
+            for (&j, &x) in cols.iter().zip(vals) {
+                row_idx[next[j]] = i;
+                values[next[j]] = x;
+                next[j] += 1;
+            }
+        }
+        CscMatrix { rows: self.rows, cols: self.cols, col_ptr, row_idx, values }
+    }
+}
+
+impl<T> CsrMatrix<T> {
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn shape(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    // quantidade de elementos guardados (diferentes de zero)
+    pub fn nnz(&self) -> usize {
+        self.values.len()
+    }
+
+    // colunas e valores guardados da linha i
+    pub fn row(&self, i: usize) -> (&[usize], &[T]) {
+        let range = self.row_ptr[i]..self.row_ptr[i + 1];
+        (&self.col_idx[range.clone()], &self.values[range])
+    }
+}
+
+impl<T: Numeric> CscMatrix<T> {
+    // converte de uma matriz densa, ignorando os zeros
+    pub fn from_dense(m: &Matrix<T>) -> CscMatrix<T> {
+        CsrMatrix::from_dense(m).to_csc()
+    }
+
+    pub fn to_dense(&self) -> Matrix<T> {
+        let mut m = Matrix::new(self.rows, self.cols);
+        for j in 0..self.cols {
+            for n in self.col_ptr[j]..self.col_ptr[j + 1] {
+                m[(self.row_idx[n], j)] = self.values[n];
+            }
+        }
+        m
+    }
+
+    // converte para CSR, do mesmo jeito que CsrMatrix::to_csc, trocando linhas por colunas
+    pub fn to_csr(&self) -> CsrMatrix<T> {
+        let mut row_ptr = vec![0; self.rows + 1];
+        for &i in &self.row_idx {
+            row_ptr[i + 1] += 1;
+        }
+        for i in 0..self.rows {
+            row_ptr[i + 1] += row_ptr[i];
+        }
+        let mut next = row_ptr.clone();
+        let mut col_idx = vec![0; self.values.len()];
+        let mut values = vec![T::default(); self.values.len()];
+        for j in 0..self.cols {
+            for n in self.col_ptr[j]..self.col_ptr[j + 1] {
+                let i = self.row_idx[n];
+                col_idx[next[i]] = j;
+                values[next[i]] = self.values[n];
+                next[i] += 1;
+            }
+        }
+        CsrMatrix { rows: self.rows, cols: self.cols, row_ptr, col_idx, values }
+    }
+
+    pub fn nnz(&self) -> usize {
+        self.values.len()
+    }
+}
+
+// converte do formato antigo Vec<Vec<T>>, passando pela matriz densa
+impl<T: Numeric> TryFrom<Vec<Vec<T>>> for CsrMatrix<T> {
+    type Error = MatrixError;
+
+    fn try_from(rows: Vec<Vec<T>>) -> Result<CsrMatrix<T>, MatrixError> {
+        Ok(CsrMatrix::from_dense(&Matrix::try_from(rows)?))
+    }
+}
+
+// verifica se o vetor x tem o tamanho das colunas de a
+fn check_spmv_shape<T>(a: &CsrMatrix<T>, x: &[T]) -> Result<(), MatrixError> {
+    if a.rows == 0 || a.cols == 0 {
+        return Err(MatrixError::Empty);
+    }
+    if a.cols != x.len() {
+        return Err(MatrixError::ShapeMismatch { left: a.shape(), right: (x.len(), 1), op: "spmv" });
+    }
+    Ok(())
+}
+
+// verifica se as colunas de a sao iguais as linhas de b
+fn check_spgemm_shape<T>(a: &CsrMatrix<T>, b: &CsrMatrix<T>) -> Result<(), MatrixError> {
+    if a.rows == 0 || a.cols == 0 || b.rows == 0 || b.cols == 0 {
+        return Err(MatrixError::Empty);
+    }
+    if a.cols != b.rows {
+        return Err(MatrixError::ShapeMismatch { left: a.shape(), right: b.shape(), op: "spgemm" });
+    }
+    Ok(())
+}
+
+// calcula y[r] = linha start + r de a vezes x, so passando pelos elementos guardados
+fn spmv_rows<T: Numeric>(a: &CsrMatrix<T>, x: &[T], start: usize, y: &mut [T]) {
+    for (r, out) in y.iter_mut().enumerate() {
+        let (cols, values) = a.row(start + r);
+        let mut sum = T::default();
+        for (&j, &v) in cols.iter().zip(values) {
+            sum += v * x[j];
+        }
+        *out = sum;
+    }
+}
+
+// multiplicacao de matriz esparsa por vetor denso, y = a * x
+pub fn spmv_sequential<T: Numeric>(a: &CsrMatrix<T>, x: &[T]) -> Result<Vec<T>, MatrixError> {
+    check_spmv_shape(a, x)?;
+    let mut y = vec![T::default(); a.rows];
+    spmv_rows(a, x, 0, &mut y);
+    Ok(y)
+}
+
+// a mesma multiplicacao usando threads, cada parte calcula um grupo de linhas de y
+pub fn spmv_threads<T: Numeric>(a: &CsrMatrix<T>, x: &[T], thr: usize) -> Result<Vec<T>, MatrixError> {
+    check_spmv_shape(a, x)?;
+    let mut y = vec![T::default(); a.rows];
+    pool::global().scope(|s| {
+        for (start, chunk) in split_rows_mut(&mut y, a.rows, 1, thr) {
+            s.spawn(move || spmv_rows(a, x, start, chunk));
+        }
+    });
+    Ok(y)
+}
+
+// linhas start..end do produto a * b pelo algoritmo de Gustavson:
+// para cada elemento a[i][k], soma a[i][k] * (linha k de b) em um acumulador denso da linha i
+// devolve a quantidade de elementos de cada linha, as colunas e os valores, sem os zeros
+fn spgemm_rows<T: Numeric>(a: &CsrMatrix<T>, b: &CsrMatrix<T>, start: usize, end: usize) -> (Vec<usize>, Vec<usize>, Vec<T>) {
+    let zero = T::default();
+    let mut acc = vec![zero; b.cols];
+    let mut touched = vec![false; b.cols];
+    let mut used = Vec::new();
+
+    let mut counts = Vec::with_capacity(end - start);
+    let mut col_idx = Vec::new();
+    let mut values = Vec::new();
+    for i in start..end {
+        let (a_cols, a_values) = a.row(i);
+        for (&k, &aik) in a_cols.iter().zip(a_values) {
+            let (b_cols, b_values) = b.row(k);
+            for (&j, &bkj) in b_cols.iter().zip(b_values) {
+                if !touched[j] {
+                    touched[j] = true;
+                    used.push(j);
+                }
+                acc[j] += aik * bkj;
+            }
+        }
+        // as colunas de cada linha ficam em ordem crescente, igual a from_dense
+        used.sort_unstable();
+        let before = col_idx.len();
+        for &j in &used {
+            if acc[j] != zero {
+                col_idx.push(j);
+                values.push(acc[j]);
+            }
+            acc[j] = zero;
+            touched[j] = false;
+        }
+        counts.push(col_idx.len() - before);
+        used.clear();
+    }
+    (counts, col_idx, values)
+}
+
+// junta os pedacos de linhas calculados por spgemm_rows em uma CsrMatrix
+fn assemble<T>(rows: usize, cols: usize, parts: Vec<(Vec<usize>, Vec<usize>, Vec<T>)>) -> CsrMatrix<T> {
+    let mut row_ptr = Vec::with_capacity(rows + 1);
+    row_ptr.push(0);
+    let mut col_idx = Vec::new();
+    let mut values = Vec::new();
+    for (counts, mut part_cols, mut part_values) in parts {
+        for count in counts {
+            row_ptr.push(row_ptr.last().unwrap() + count);
+        }
+        col_idx.append(&mut part_cols);
+        values.append(&mut part_values);
+    }
+    CsrMatrix { rows, cols, row_ptr, col_idx, values }
+}
+
+// multiplicacao de duas matrizes esparsas, o resultado tambem e esparso
+pub fn spgemm_sequential<T: Numeric>(a: &CsrMatrix<T>, b: &CsrMatrix<T>) -> Result<CsrMatrix<T>, MatrixError> {
+    check_spgemm_shape(a, b)?;
+    let part = spgemm_rows(a, b, 0, a.rows);
+    Ok(assemble(a.rows, b.cols, vec![part]))
+}
+
+// multiplicacao de duas matrizes esparsas usando threads
+// cada parte calcula um grupo de linhas separado, e no final os pedacos sao juntados em ordem
+pub fn spgemm_threads<T: Numeric>(a: &CsrMatrix<T>, b: &CsrMatrix<T>, thr: usize) -> Result<CsrMatrix<T>, MatrixError> {
+    check_spgemm_shape(a, b)?;
+    let thr = pool::effective_threads(thr, a.rows);
+    let mut parts = vec![(Vec::new(), Vec::new(), Vec::new()); thr];
+    pool::global().scope(|s| {
+        for (n, part) in parts.iter_mut().enumerate() {
+            let start = n * a.rows / thr;
+            let end = (n + 1) * a.rows / thr;
+            s.spawn(move || *part = spgemm_rows(a, b, start, end));
+        }
+    });
+    Ok(assemble(a.rows, b.cols, parts))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ops::mul_matrix_sequential;
+    use crate::random::{create_matrix_from_random, create_sparse_matrix_from_random};
+    use crate::testing::assert_threads_match;
+
+    // conversoes de ida e volta entre denso, CSR e CSC
+    #[test]
+    fn conversions_round_trip() -> Result<(), MatrixError> {
+        let a = create_sparse_matrix_from_random(60, 80, 0.1, 10);
+        let dense = a.to_dense();
+        assert_eq!(CsrMatrix::from_dense(&dense), a);
+        assert_eq!(a.to_csc().to_dense(), dense);
+        assert_eq!(CscMatrix::from_dense(&dense).to_csr(), a);
+        assert_eq!(a.to_csc().nnz(), a.nnz());
+        assert_eq!(CsrMatrix::try_from(dense.to_rows())?, a);
+        Ok(())
+    }
+
+    // posicoes repetidas sao somadas, as que somam zero somem, e indices fora da matriz sao erro
+    #[test]
+    fn from_triplets_merges_and_validates() -> Result<(), MatrixError> {
+        let m = CsrMatrix::from_triplets(3, 2, vec![(2, 1, 4), (0, 0, 1), (2, 1, -1), (1, 0, 5), (1, 0, -5)])?;
+        assert_eq!(m.triplets(), vec![(0, 0, 1), (2, 1, 3)]);
+        assert_eq!(
+            CsrMatrix::from_triplets(3, 2, vec![(0, 1, 1), (0, 2, 1)]),
+            Err(MatrixError::IndexOutOfBounds { row: 0, col: 2, rows: 3, cols: 2 })
+        );
+        Ok(())
+    }
+
+    // esparsa vezes vetor, comparada com a matriz densa vezes uma matriz de uma coluna
+    #[test]
+    fn spmv_matches_dense() -> Result<(), MatrixError> {
+        let a = create_sparse_matrix_from_random(60, 80, 0.1, 10);
+        let x = create_matrix_from_random(80, 1, 10);
+        let expected = mul_matrix_sequential(&a.to_dense(), &x)?.into_vec();
+        assert_eq!(spmv_sequential(&a, x.as_slice())?, expected);
+        assert_threads_match(Ok(expected), |thr| spmv_threads(&a, x.as_slice(), thr));
+        assert_eq!(spmv_sequential(&a, &[1; 3]), Err(MatrixError::ShapeMismatch { left: (60, 80), right: (3, 1), op: "spmv" }));
+        assert_eq!(spmv_threads(&CsrMatrix::from_dense(&Matrix::new(0, 3)), &[1; 3], 2), Err(MatrixError::Empty));
+        Ok(())
+    }
+
+    // esparsa vezes esparsa, comparada com a multiplicacao densa
+    #[test]
+    fn spgemm_matches_dense() -> Result<(), MatrixError> {
+        let a = create_sparse_matrix_from_random(60, 80, 0.1, 10);
+        let b = create_sparse_matrix_from_random(80, 50, 0.1, 10);
+        let expected = mul_matrix_sequential(&a.to_dense(), &b.to_dense())?;
+        let product = spgemm_sequential(&a, &b)?;
+        assert_eq!((product.rows(), product.cols()), (a.rows(), b.cols()));
+        assert_eq!(product.to_dense(), expected);
+        assert_eq!(product, CsrMatrix::from_dense(&expected));
+        assert_threads_match(Ok(product), |thr| spgemm_threads(&a, &b, thr));
+        assert!(matches!(spgemm_sequential(&a, &a), Err(MatrixError::ShapeMismatch { .. })));
+
+        // matrizes sem nenhum elemento diferente de zero
+        let zeros: CsrMatrix<i32> = CsrMatrix::from_dense(&Matrix::new(5, 4));
+        assert_eq!(spgemm_threads(&zeros, &CsrMatrix::from_dense(&Matrix::new(4, 3)), 3)?.nnz(), 0);
+        Ok(())
+    }
+}
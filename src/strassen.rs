@@ -0,0 +1,270 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use crate::matrix::{Matrix, MatrixError};
+use crate::numeric::{Integer, Numeric};
+use crate::ops::{check_mul_shape, mul_matrix_blocked_threads_with, mul_matrix_sequential, Overflow, DEFAULT_TILE};
+use crate::pool;
+
+// abaixo deste tamanho a recursao para e usamos a multiplicacao simples
+pub const DEFAULT_CUTOFF: usize = 64;
+// quantos niveis da recursao tem os produtos divididos entre as threads, 7^depth produtos no total
+pub const DEFAULT_DEPTH: usize = 1;
+
+// multiplicacao de Strassen: divide as matrizes em 4 quadrantes e faz 7 multiplicacoes em vez de 8
+// os primeiros depth niveis da recursao sao abertos antes, gerando ate 7^depth produtos independentes,
+// que rodam no pool com strassen_threads(depth, thr) trabalhos; depois os resultados sao juntados de volta
+// quando o tamanho chega em cutoff, usa a mul_matrix_sequential
+// matrizes que nao sao quadradas ou nao tem tamanho potencia de 2 sao completadas com zeros
+// com inteiros, as somas intermediarias (A11 + A22, P1 + P4 - P5 + P7, ...) podem passar do limite do tipo,
+// e com tipos sem sinal (u8, u32, ...) as subtracoes ficam negativas, mesmo quando o produto final cabe
+// por isso as contas sao feitas em T::Intermediate (std::num::Wrapping para inteiros), exata modulo 2^bits:
+// os estouros intermediarios se cancelam e o resultado e o da multiplicacao comum quando ele cabe no tipo,
+// e o da multiplicacao com volta quando nao cabe; para verificar ou saturar, use mul_matrix_strassen_with
+pub fn mul_matrix_strassen<T: Numeric>(m1: &Matrix<T>, m2: &Matrix<T>, cutoff: usize, depth: usize, thr: usize) -> Result<Matrix<T>, MatrixError> {
+    check_mul_shape(m1, m2, "mul_strassen")?;
+    let rows = m1.rows();
+    let cols = m2.cols();
+
+    // tamanho da matriz quadrada, potencia de 2, que cabe as duas entradas
+    let n = rows.max(m1.cols()).max(cols).next_power_of_two();
+    let a = pad(&m1.map(|&x| x.to_intermediate()), n);
+    let b = pad(&m2.map(|&x| x.to_intermediate()), n);
+    let cutoff = cutoff.max(1);
+
+    // abre os primeiros niveis, guardando as entradas de cada produto folha
+    let mut leaves = Vec::new();
+    let plan = expand(a, b, cutoff, depth, &mut leaves);
+    let products = run_leaves(&leaves, cutoff, thr);
+    let c = assemble(&plan, &mut products.into_iter().map(Some).collect::<Vec<_>>());
+
+    // tira o preenchimento com zeros, ficando com rows x cols
+    let mut result = Matrix::new(rows, cols);
+    for i in 0..rows {
+        for (out, &x) in result.row_mut(i).iter_mut().zip(&c.row(i)[..cols]) {
+            *out = T::from_intermediate(x);
+        }
+    }
+    Ok(result)
+}
+
+// Strassen com o tratamento de estouro escolhido por mode, com o mesmo resultado das outras multiplicacoes *_with
+// com Wrapping e a propria mul_matrix_strassen, que ja faz as contas em std::num::Wrapping
+// com Checked e Saturating nao da para saber pelas somas intermediarias se o produto estoura
+// (elas podem estourar com o produto cabendo no tipo), entao esses modos usam a multiplicacao em blocos,
+// com as mesmas strassen_threads(depth, thr) threads
+pub fn mul_matrix_strassen_with<T: Integer>(m1: &Matrix<T>, m2: &Matrix<T>, cutoff: usize, depth: usize, thr: usize, mode: Overflow) -> Result<Matrix<T>, MatrixError> {
+    check_mul_shape(m1, m2, "mul_strassen")?;
+    match mode {
+        Overflow::Wrapping => mul_matrix_strassen(m1, m2, cutoff, depth, thr),
+        Overflow::Checked | Overflow::Saturating => mul_matrix_blocked_threads_with(m1, m2, DEFAULT_TILE, strassen_threads(depth, thr), mode),
+    }
+}
+
+// quantas threads a multiplicacao de Strassen usa com depth niveis abertos e thr threads pedidas
+// nunca mais threads do que produtos folha, nem do que o pool tem
+pub fn strassen_threads(depth: usize, thr: usize) -> usize {
+    let leaves = 7usize.saturating_pow(depth.min(u32::MAX as usize) as u32);
+    pool::running_threads(thr, leaves)
+}
+
+// arvore dos niveis abertos: cada folha e o indice de um produto em leaves,
+// e cada divisao junta os 7 produtos dos filhos nos 4 quadrantes
+enum Plan {
+    Leaf(usize),
+    Split(Vec<Plan>),
+}
+
+// abre a recursao ate depth niveis (ou ate o tamanho chegar em cutoff), guardando as entradas das folhas
+fn expand<T: Numeric>(a: Matrix<T>, b: Matrix<T>, cutoff: usize, depth: usize, leaves: &mut Vec<(Matrix<T>, Matrix<T>)>) -> Plan {
+    let n = a.rows();
+    if depth == 0 || n <= cutoff || n == 1 {
+        leaves.push((a, b));
+        return Plan::Leaf(leaves.len() - 1);
+    }
+    let children = seven(&a, &b).into_iter().map(|(x, y)| expand(x, y, cutoff, depth - 1, leaves)).collect();
+    Plan::Split(children)
+}
+
+// calcula os produtos folha no pool: cada trabalho pega o proximo produto livre por um contador atomico,
+// igual ao escalonamento dinamico da ops, e todos os trabalhos sao enviados de um unico scope,
+// sem scopes dentro de trabalhos do pool
+fn run_leaves<T: Numeric>(leaves: &[(Matrix<T>, Matrix<T>)], cutoff: usize, thr: usize) -> Vec<Matrix<T>> {
+    if leaves.len() == 1 {
+        return vec![strassen(&leaves[0].0, &leaves[0].1, cutoff)];
+    }
+    let results: Vec<Mutex<Option<Matrix<T>>>> = leaves.iter().map(|_| Mutex::new(None)).collect();
+    let next = AtomicUsize::new(0);
+    let workers = pool::effective_threads(thr, leaves.len());
+    pool::global().scope(|s| {
+        for _ in 0..workers {
+            s.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                if i >= leaves.len() {
+                    break;
+                }
+                let product = strassen(&leaves[i].0, &leaves[i].1, cutoff);
+                *results[i].lock().unwrap() = Some(product);
+            });
+        }
+    });
+    results.into_iter().map(|r| r.into_inner().unwrap().unwrap()).collect()
+}
+
+// junta os produtos de volta seguindo a arvore
+fn assemble<T: Numeric>(plan: &Plan, products: &mut [Option<Matrix<T>>]) -> Matrix<T> {
+    match plan {
+        Plan::Leaf(i) => products[*i].take().unwrap(),
+        Plan::Split(children) => {
+            let p: Vec<Matrix<T>> = children.iter().map(|child| assemble(child, products)).collect();
+            combine(p.try_into().unwrap_or_else(|_| unreachable!()))
+        }
+    }
+}
+
+// parte recursiva sequencial, a e b sao quadradas com tamanho potencia de 2
+fn strassen<T: Numeric>(a: &Matrix<T>, b: &Matrix<T>, cutoff: usize) -> Matrix<T> {
+    let n = a.rows();
+    if n <= cutoff || n == 1 {
+        // as entradas ja foram validadas, entao a multiplicacao simples nao falha
+        return mul_matrix_sequential(a, b).unwrap();
+    }
+    let products: Vec<Matrix<T>> = seven(a, b).iter().map(|(x, y)| strassen(x, y, cutoff)).collect();
+    combine(products.try_into().unwrap_or_else(|_| unreachable!()))
+}
+
+// entradas dos 7 produtos de Strassen, a partir dos quadrantes de a e b
+fn seven<T: Numeric>(a: &Matrix<T>, b: &Matrix<T>) -> [(Matrix<T>, Matrix<T>); 7] {
+    let h = a.rows() / 2;
+    let (a11, a12, a21, a22) = split(a, h);
+    let (b11, b12, b21, b22) = split(b, h);
+    [
+        (add(&a11, &a22), add(&b11, &b22)),
+        (add(&a21, &a22), b11.clone()),
+        (a11.clone(), sub(&b12, &b22)),
+        (a22.clone(), sub(&b21, &b11)),
+        (add(&a11, &a12), b22.clone()),
+        (sub(&a21, &a11), add(&b11, &b12)),
+        (sub(&a12, &a22), add(&b21, &b22)),
+    ]
+}
+
+// junta os quadrantes do resultado a partir dos 7 produtos
+fn combine<T: Numeric>([p1, p2, p3, p4, p5, p6, p7]: [Matrix<T>; 7]) -> Matrix<T> {
+    let c11 = add(&sub(&add(&p1, &p4), &p5), &p7);
+    let c12 = add(&p3, &p5);
+    let c21 = add(&p2, &p4);
+    let c22 = add(&add(&sub(&p1, &p2), &p3), &p6);
+    join(&c11, &c12, &c21, &c22)
+}
+
+// copia m para o canto de cima a esquerda de uma matriz n x n de zeros
+fn pad<T: Numeric>(m: &Matrix<T>, n: usize) -> Matrix<T> {
+    if m.rows() == n && m.cols() == n {
+        return m.clone();
+    }
+    let mut padded = Matrix::new(n, n);
+    for i in 0..m.rows() {
+        padded.row_mut(i)[..m.cols()].copy_from_slice(m.row(i));
+    }
+    padded
+}
+
+// separa m nos quadrantes (11, 12, 21, 22) de tamanho h x h
+fn split<T: Numeric>(m: &Matrix<T>, h: usize) -> (Matrix<T>, Matrix<T>, Matrix<T>, Matrix<T>) {
+    let quadrant = |r0: usize, c0: usize| {
+        let mut q = Matrix::new(h, h);
+        for i in 0..h {
+            q.row_mut(i).copy_from_slice(&m.row(r0 + i)[c0..c0 + h]);
+        }
+        q
+    };
+    (quadrant(0, 0), quadrant(0, h), quadrant(h, 0), quadrant(h, h))
+}
+
+// monta uma matriz 2h x 2h a partir dos 4 quadrantes
+fn join<T: Numeric>(c11: &Matrix<T>, c12: &Matrix<T>, c21: &Matrix<T>, c22: &Matrix<T>) -> Matrix<T> {
+    let h = c11.rows();
+    let mut m = Matrix::new(2 * h, 2 * h);
+    for i in 0..h {
+        m.row_mut(i)[..h].copy_from_slice(c11.row(i));
+        m.row_mut(i)[h..].copy_from_slice(c12.row(i));
+        m.row_mut(h + i)[..h].copy_from_slice(c21.row(i));
+        m.row_mut(h + i)[h..].copy_from_slice(c22.row(i));
+    }
+    m
+}
+
+// soma e subtracao de quadrantes do mesmo tamanho, sem validacao pois os tamanhos ja sao conhecidos
+fn add<T: Numeric>(x: &Matrix<T>, y: &Matrix<T>) -> Matrix<T> {
+    let data = x.as_slice().iter().zip(y.as_slice()).map(|(&a, &b)| a + b).collect();
+    Matrix::from_vec(x.rows(), x.cols(), data).unwrap()
+}
+
+fn sub<T: Numeric>(x: &Matrix<T>, y: &Matrix<T>) -> Matrix<T> {
+    let data = x.as_slice().iter().zip(y.as_slice()).map(|(&a, &b)| a - b).collect();
+    Matrix::from_vec(x.rows(), x.cols(), data).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ops::mul_matrix_sequential_with;
+    use crate::random::create_matrix_from_random;
+    use crate::testing::assert_threads_match;
+
+    // tamanhos que nao sao potencia de 2 nem quadrados, com cutoff pequeno para a recursao ir fundo
+    #[test]
+    fn matches_ordinary_mul() {
+        let m1 = create_matrix_from_random(19, 13, 10);
+        let m2 = create_matrix_from_random(13, 22, 10);
+        let expected = mul_matrix_sequential(&m1, &m2);
+        for depth in [0, 1, 2] {
+            assert_threads_match(expected.clone(), |thr| mul_matrix_strassen(&m1, &m2, 4, depth, thr));
+        }
+        assert!(matches!(mul_matrix_strassen(&m1, &m1, 4, 1, 2), Err(MatrixError::ShapeMismatch { .. })));
+    }
+
+    // com u8 as subtracoes dos quadrantes (B12 - B22, A21 - A11, ...) ficam negativas
+    // mas o produto cabe, entao o resultado e o mesmo da multiplicacao comum, sem panic em debug
+    #[test]
+    fn unsigned_matches_ordinary_mul() {
+        let m1 = create_matrix_from_random(9, 6, 4).map(|&x| x as u8);
+        let m2 = create_matrix_from_random(6, 11, 4).map(|&x| x as u8);
+        let expected = mul_matrix_sequential(&m1, &m2);
+        for depth in [0, 1, 2] {
+            assert_threads_match(expected.clone(), |thr| mul_matrix_strassen(&m1, &m2, 1, depth, thr));
+        }
+    }
+
+    // perto do limite de i32 as somas intermediarias estouram, mas o produto final cabe
+    #[test]
+    fn near_overflow_matches_ordinary_mul() -> Result<(), MatrixError> {
+        let big = i32::MAX - 1;
+        let a = Matrix::try_from(vec![vec![big, 1, 0], vec![1, big, -big], vec![0, -big, big]])?;
+        let b = Matrix::try_from(vec![vec![1, 0, 0], vec![0, 1, 1], vec![0, 1, 1]])?;
+        let expected = mul_matrix_sequential(&a, &b);
+        for depth in [0, 1] {
+            assert_threads_match(expected.clone(), |thr| mul_matrix_strassen(&a, &b, 1, depth, thr));
+        }
+        Ok(())
+    }
+
+    // as somas intermediarias estouram (a11 + a22 = 2 * i32::MAX), mas o produto cabe em i32
+    // com Wrapping o resultado e o mesmo da multiplicacao comum, e os outros modos seguem a multiplicacao em blocos
+    #[test]
+    fn overflow_modes_match_other_kernels() -> Result<(), MatrixError> {
+        let a = Matrix::try_from(vec![vec![i32::MAX, 0], vec![0, i32::MAX]])?;
+        let b = Matrix::try_from(vec![vec![1, 0], vec![0, -1]])?;
+        for mode in [Overflow::Checked, Overflow::Wrapping, Overflow::Saturating] {
+            let expected = mul_matrix_sequential_with(&a, &b, mode);
+            assert_threads_match(expected, |thr| mul_matrix_strassen_with(&a, &b, 1, 1, thr, mode));
+        }
+        assert_eq!(mul_matrix_strassen_with(&a, &b, 1, 1, 2, Overflow::Wrapping)?.to_rows(), vec![vec![i32::MAX, 0], vec![0, -i32::MAX]]);
+
+        // com o produto estourando, Checked reporta o elemento como as outras multiplicacoes
+        let twos = Matrix::filled(2, 2, 2);
+        assert_eq!(mul_matrix_strassen_with(&a, &twos, 1, 1, 2, Overflow::Checked), Err(MatrixError::Overflow { row: 0, col: 0, op: "mul" }));
+        Ok(())
+    }
+}
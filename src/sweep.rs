@@ -0,0 +1,240 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::bench::{self, Stats};
+use crate::formats::FormatError;
+use crate::report::{csv_field, report_format, Host, ReportFormat};
+
+// uma medicao da varredura: a operacao em uma matriz size x size com threads threads,
+// junto com a versao sequencial no mesmo tamanho
+#[derive(Debug, Clone, PartialEq)]
+pub struct SweepPoint {
+    pub operation: &'static str,
+    pub size: usize,
+    // quantidade de threads realmente usada: sem pool::AUTO, limitada ao tamanho e ao pool global
+    pub threads: usize,
+    pub sequential: Stats,
+    pub parallel: Stats,
+}
+
+impl SweepPoint {
+    // quantas vezes a versao com threads e mais rapida que a sequencial
+    pub fn speedup(&self) -> f64 {
+        bench::speedup(&self.sequential, &self.parallel)
+    }
+
+    // speedup dividido pela quantidade de threads, 1.0 seria o ganho ideal
+    pub fn efficiency(&self) -> f64 {
+        self.speedup() / self.threads as f64
+    }
+
+    // estimativa da fracao serial pela lei de Amdahl (metrica de Karp-Flatt):
+    // isolando f em speedup = 1 / (f + (1 - f) / p), f = (1 / speedup - 1 / p) / (1 - 1 / p)
+    // com uma unica thread nao da para separar a parte serial, entao nao existe
+    pub fn serial_fraction(&self) -> Option<f64> {
+        if self.threads < 2 {
+            return None;
+        }
+        let p = self.threads as f64;
+        Some((1.0 / self.speedup() - 1.0 / p) / (1.0 - 1.0 / p))
+    }
+}
+
+// medicao como aparece no JSON, com as metricas ja calculadas
+// tempos infinitos ou NaN viram null, que o JSON aceita
+#[derive(Serialize)]
+struct PointJson<'a> {
+    operation: &'a str,
+    size: usize,
+    threads: usize,
+    sequential: &'a Stats,
+    parallel: &'a Stats,
+    speedup: f64,
+    efficiency: f64,
+    serial_fraction: Option<f64>,
+}
+
+impl<'a> PointJson<'a> {
+    fn new(point: &'a SweepPoint) -> PointJson<'a> {
+        PointJson {
+            operation: point.operation,
+            size: point.size,
+            threads: point.threads,
+            sequential: &point.sequential,
+            parallel: &point.parallel,
+            speedup: point.speedup(),
+            efficiency: point.efficiency(),
+            serial_fraction: point.serial_fraction(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct SweepJson<'a> {
+    seed: u64,
+    host: &'a Host,
+    points: Vec<PointJson<'a>>,
+}
+
+// resultado completo de uma varredura
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sweep {
+    pub seed: u64,
+    pub host: Host,
+    pub points: Vec<SweepPoint>,
+}
+
+impl Sweep {
+    // mostra a tabela com uma linha por medicao
+    pub fn print_table(&self) {
+        println!(
+            "Sweep - Seed: {}, {} cores, pool with {} threads",
+            self.seed, self.host.cores, self.host.pool_threads
+        );
+        println!(
+            "{:<10} {:>6} {:>8} {:>16} {:>16} {:>8} {:>10} {:>8}",
+            "operation", "size", "threads", "sequential ns", "threads ns", "speedup", "efficiency", "serial"
+        );
+        for point in &self.points {
+            let serial = match point.serial_fraction() {
+                Some(f) => format!("{:.3}", f),
+                None => "-".to_string(),
+            };
+            println!(
+                "{:<10} {:>6} {:>8} {:>16.0} {:>16.0} {:>8.2} {:>10.2} {:>8}",
+                point.operation,
+                point.size,
+                point.threads,
+                point.sequential.median,
+                point.parallel.median,
+                point.speedup(),
+                point.efficiency(),
+                serial
+            );
+        }
+    }
+
+    // uma linha por medicao, com a semente repetida em todas para cada linha poder ser reproduzida sozinha
+    pub fn write_csv(&self, mut writer: impl Write) -> Result<(), FormatError> {
+        writeln!(
+            writer,
+            "seed,hostname,os,arch,cores,pool_threads,operation,size,threads,sequential_median_ns,threads_median_ns,threads_min_ns,threads_mean_ns,threads_stddev_ns,speedup,efficiency,serial_fraction"
+        )?;
+        for point in &self.points {
+            let serial = point.serial_fraction().map_or(String::new(), |f| f.to_string());
+            writeln!(
+                writer,
+                "{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
+                self.seed,
+                csv_field(&self.host.hostname),
+                csv_field(&self.host.os),
+                csv_field(&self.host.arch),
+                self.host.cores,
+                self.host.pool_threads,
+                csv_field(point.operation),
+                point.size,
+                point.threads,
+                point.sequential.median,
+                point.parallel.median,
+                point.parallel.min,
+                point.parallel.mean,
+                point.parallel.stddev,
+                point.speedup(),
+                point.efficiency(),
+                serial
+            )?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    pub fn write_json(&self, mut writer: impl Write) -> Result<(), FormatError> {
+        let points = self.points.iter().map(PointJson::new).collect();
+        serde_json::to_writer_pretty(&mut writer, &SweepJson { seed: self.seed, host: &self.host, points })?;
+        writeln!(writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    // grava em CSV ou JSON, escolhido pela extensao do arquivo
+    pub fn write_file(&self, path: &Path) -> Result<(), FormatError> {
+        let format = report_format(path)?;
+        let writer = BufWriter::new(File::create(path)?);
+        match format {
+            ReportFormat::Csv => self.write_csv(writer),
+            ReportFormat::Json => self.write_json(writer),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(threads: usize, sequential: u64, parallel: u64) -> SweepPoint {
+        SweepPoint {
+            operation: "sum",
+            size: 100,
+            threads,
+            sequential: Stats::from_samples(&[sequential]),
+            parallel: Stats::from_samples(&[parallel, parallel]),
+        }
+    }
+
+    fn sweep() -> Sweep {
+        let host = Host { hostname: "lab,01".to_string(), os: "linux".to_string(), arch: "x86_64".to_string(), cores: 8, pool_threads: 8 };
+        Sweep { seed: 7, host, points: vec![point(4, 400, 200), point(1, 400, 400)] }
+    }
+
+    // speedup 2 com 4 threads: eficiencia 0.5 e fracao serial (1/2 - 1/4) / (1 - 1/4) = 1/3
+    #[test]
+    fn metrics() {
+        let p = point(4, 400, 200);
+        assert_eq!((p.speedup(), p.efficiency()), (2.0, 0.5));
+        assert!((p.serial_fraction().unwrap() - 1.0 / 3.0).abs() < 1e-12);
+
+        // speedup ideal, sem parte serial
+        assert!(point(8, 800, 100).serial_fraction().unwrap().abs() < 1e-12);
+        assert_eq!(point(1, 400, 400).serial_fraction(), None);
+        assert_eq!(point(0, 400, 400).serial_fraction(), None);
+    }
+
+    #[test]
+    fn csv_header_and_rows() -> Result<(), FormatError> {
+        let mut out = Vec::new();
+        sweep().write_csv(&mut out)?;
+        let text = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(
+            lines[0],
+            "seed,hostname,os,arch,cores,pool_threads,operation,size,threads,sequential_median_ns,threads_median_ns,threads_min_ns,threads_mean_ns,threads_stddev_ns,speedup,efficiency,serial_fraction"
+        );
+        // o nome com virgula fica entre aspas, e sem fracao serial a coluna fica vazia
+        assert_eq!(lines[1], "7,\"lab,01\",linux,x86_64,8,8,sum,100,4,400,200,200,200,0,2,0.5,0.3333333333333333");
+        assert_eq!(lines[2], "7,\"lab,01\",linux,x86_64,8,8,sum,100,1,400,400,400,400,0,1,1,");
+        Ok(())
+    }
+
+    #[test]
+    fn json_points() -> Result<(), FormatError> {
+        let mut out = Vec::new();
+        sweep().write_json(&mut out)?;
+        let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(json["seed"], 7);
+        assert_eq!(json["host"]["hostname"], "lab,01");
+        let points = json["points"].as_array().unwrap();
+        assert_eq!(points.len(), 2);
+        assert_eq!(points[0]["operation"], "sum");
+        assert_eq!(points[0]["threads"], 4);
+        assert_eq!(points[0]["sequential"]["median_ns"], 400.0);
+        assert_eq!(points[0]["parallel"]["samples"], 2);
+        assert_eq!((points[0]["speedup"].as_f64(), points[0]["efficiency"].as_f64()), (Some(2.0), Some(0.5)));
+        assert!((points[0]["serial_fraction"].as_f64().unwrap() - 1.0 / 3.0).abs() < 1e-12);
+        assert!(points[1]["serial_fraction"].is_null());
+        Ok(())
+    }
+}
@@ -0,0 +1,36 @@
+// funcoes usadas pelos testes de varios modulos
+use std::fmt::Debug;
+
+use crate::matrix::{Matrix, MatrixError};
+use crate::pool;
+use crate::semiring::{mul_matrix_semiring_sequential, mul_matrix_semiring_threads, Semiring};
+
+// quantidades de threads testadas nas versoes com threads:
+// pool::AUTO, 1, uma divisao nao exata, uma exata e mais threads do que trabalho
+pub const THREAD_COUNTS: [usize; 5] = [pool::AUTO, 1, 3, 4, 100];
+
+// tolerancia das decomposicoes, que acumulam erro de arredondamento ao longo de muitas operacoes
+pub const LINALG_TOLERANCE: f64 = 1e-8;
+
+// a versao com threads precisa dar exatamente o resultado da sequencial, com qualquer quantidade de threads
+// threads recebe a quantidade de threads; com um Result, os erros tambem precisam ser os mesmos
+pub fn assert_threads_match<R: PartialEq + Debug>(sequential: R, threads: impl Fn(usize) -> R) {
+    for thr in THREAD_COUNTS {
+        assert_eq!(threads(thr), sequential, "threaded result differs from the sequential one with {} threads", thr);
+    }
+}
+
+// multiplica a matriz de adjacencia por ela mesma ate cobrir caminhos de ate n - 1 arestas,
+// usando a versao sequencial e a com threads, que precisam concordar
+pub fn semiring_paths<S: Semiring>(adjacency: &Matrix<S::Elem>) -> Result<Matrix<S::Elem>, MatrixError> {
+    let mut sequential = adjacency.clone();
+    let mut threads = adjacency.clone();
+    let mut edges = 1;
+    while edges < adjacency.rows() - 1 {
+        sequential = mul_matrix_semiring_sequential::<S>(&sequential, &sequential)?;
+        threads = mul_matrix_semiring_threads::<S>(&threads, &threads, 3)?;
+        edges *= 2;
+    }
+    assert_eq!(sequential, threads);
+    Ok(sequential)
+}